@@ -1,20 +1,23 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Uint128, Addr,
+use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Storage, Uint128, Addr,
         CosmosMsg, WasmMsg, Order, Decimal};
 use cw2::set_contract_version;
 use cw20::{Cw20ReceiveMsg};
 
 use cw_storage_plus::{Bound};
 
-use zerosum::referral::{ExecuteMsg as ReferralExecuteMsg};
+use zerosum::referral::{ExecuteMsg as ReferralExecuteMsg, edge_msg};
 use zerosum::asset::{Asset, AssetInfo, token_asset};
 use zerosum::round::{RoundInfo, get_round};
 use zerosum::reward::{reward_msg};
+use zerosum::jackpot::{fund_msg};
+use zerosum::game::{cap_output};
+use zerosum::claim::{claim_accounts, set_claim_delegate};
 
 use crate::error::ContractError;
-use zerosum::house::{PoolResponse, ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg};
-use crate::state::{State, STATE, PoolInfo, POOLS, GameInfo, GAMES, DEPOSITS, DepositInfo, ROUNDS};
+use zerosum::house::{PoolResponse, GameInfo, BetInfo, SimulateSettleResponse, ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg};
+use crate::state::{State, STATE, PoolInfo, POOLS, GAMES, DEPOSITS, DepositInfo, ROUNDS, ACCOUNTS, ACCOUNT_TOTALS, CLAIM_DELEGATES, BETS};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:house";
@@ -36,6 +39,9 @@ pub fn instantiate(
         distributor_contract: msg.distributor_contract.unwrap_or(Addr::unchecked("")),
         reward_contract: msg.reward_contract.unwrap_or(Addr::unchecked("")),
         max_output_rate: msg.max_output_rate.unwrap_or_default(),
+        jackpot_contract: msg.jackpot_contract.unwrap_or(Addr::unchecked("")),
+        jackpot_rate: msg.jackpot_rate.unwrap_or_default(),
+        referral_edge_rate: msg.referral_edge_rate.unwrap_or_default(),
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
@@ -63,6 +69,9 @@ pub fn execute(
             distributor_contract,
             reward_contract,
             max_output_rate,
+            jackpot_contract,
+            jackpot_rate,
+            referral_edge_rate,
         } => {
             execute_update(
                 deps, 
@@ -75,6 +84,9 @@ pub fn execute(
                 distributor_contract,
                 reward_contract,
                 max_output_rate,
+                jackpot_contract,
+                jackpot_rate,
+                referral_edge_rate,
             )
         }
         ExecuteMsg::CreatePool { asset, swap_contract, reward_weight } => 
@@ -89,11 +101,16 @@ pub fn execute(
             execute_deposit(deps, env, deposit_asset, info.sender)
         },
         ExecuteMsg::Withdraw { asset_info } => execute_withdraw(deps, env, info, asset_info),
-        ExecuteMsg::Claim { asset_info } => execute_claim(deps, env, info, asset_info),
-        ExecuteMsg::Settle { player, output } => {
+        ExecuteMsg::Claim { asset_info, owner, recipient } => {
+            let (owner, recipient) = claim_accounts(deps.storage, deps.api, &CLAIM_DELEGATES, &info.sender, owner, recipient)?
+                .ok_or(ContractError::Unauthorized {})?;
+            execute_claim(deps, env, owner, recipient, asset_info)
+        },
+        ExecuteMsg::SetClaimDelegate { delegate } => execute_set_claim_delegate(deps, info, delegate),
+        ExecuteMsg::Settle { player, output, bet_id } => {
             let coin = info.funds[0].clone();
             let asset_info = AssetInfo::NativeToken { denom: coin.denom };
-            execute_settle(deps, env, info.sender, player, coin.amount, output, asset_info)
+            execute_settle(deps, env, info.sender, player, coin.amount, output, asset_info, bet_id)
         },
         ExecuteMsg::AddGame { name, description, url, address, creator } => {
             execute_add_game(deps, info, name, description, url, address, creator)
@@ -103,7 +120,16 @@ pub fn execute(
         },
         ExecuteMsg::Collect {} => {
             execute_collect(deps, env)
-        }
+        },
+        ExecuteMsg::FundAccount {} => {
+            let fund_asset = Asset::from(info.funds[0].clone());
+            execute_fund_account(deps, fund_asset, info.sender)
+        },
+        ExecuteMsg::WithdrawAccount { asset_info, amount } => execute_withdraw_account(deps, info, asset_info, amount),
+        ExecuteMsg::DebitBet { player, asset, bet_id, payout, resolve_height } =>
+            execute_debit_bet(deps, env, info, player, asset, bet_id, payout, resolve_height),
+        ExecuteMsg::CreditWin { bet_id, amount } => execute_credit_win(deps, env, info, bet_id, amount),
+        ExecuteMsg::RemoveBet { game, bet_id } => execute_remove_bet(deps, info, game, bet_id),
     }
 }
 
@@ -118,6 +144,9 @@ fn execute_update(
     distributor_contract: Option<Addr>,
     reward_contract: Option<Addr>,
     max_output_rate: Option<Decimal>,
+    jackpot_contract: Option<Addr>,
+    jackpot_rate: Option<Decimal>,
+    referral_edge_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let mut state: State = STATE.load(deps.storage)?;
     if state.gov_contract != info.sender {
@@ -147,6 +176,15 @@ fn execute_update(
     if max_output_rate.is_some() {
         state.max_output_rate = max_output_rate.unwrap();
     }
+    if jackpot_contract.is_some() {
+        state.jackpot_contract = jackpot_contract.unwrap();
+    }
+    if jackpot_rate.is_some() {
+        state.jackpot_rate = jackpot_rate.unwrap();
+    }
+    if referral_edge_rate.is_some() {
+        state.referral_edge_rate = referral_edge_rate.unwrap();
+    }
     STATE.save(deps.storage, &state)?;
     Ok(Response::new().add_attribute("method", "update_state"))
 }
@@ -157,13 +195,17 @@ fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, cw20_msg: Cw20Receiv
             let deposit_asset = token_asset(info.sender, cw20_msg.amount);
             execute_deposit(deps, env, deposit_asset, Addr::unchecked(cw20_msg.sender))
         },
-        Ok(Cw20HookMsg::Settle { player, output }) => {
+        Ok(Cw20HookMsg::Settle { player, output, bet_id }) => {
             let asset_info = AssetInfo::Token { contract_addr: info.sender };
-            execute_settle(deps, env, Addr::unchecked(cw20_msg.sender), player, cw20_msg.amount, output, asset_info)
+            execute_settle(deps, env, Addr::unchecked(cw20_msg.sender), player, cw20_msg.amount, output, asset_info, bet_id)
         },
         Ok(Cw20HookMsg::AddRound { key, round }) => {
             execute_add_round(deps, env, info, key, round, Addr::unchecked(cw20_msg.sender), cw20_msg.amount)
         }
+        Ok(Cw20HookMsg::FundAccount {}) => {
+            let fund_asset = token_asset(info.sender, cw20_msg.amount);
+            execute_fund_account(deps, fund_asset, Addr::unchecked(cw20_msg.sender))
+        }
         Err(err) => Err(ContractError::Std(err)),
     }
 }
@@ -172,7 +214,7 @@ fn execute_collect(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut msgs: Vec<CosmosMsg> = vec![];
     POOLS.range(deps.storage, None, None, Order::Ascending).for_each(|pool| {
         let (_key, pool_info) : (_, PoolInfo) = pool.unwrap();
-        let pool_balance = pool_info.asset_info.query_balance(&deps.querier, env.contract.address.clone()).unwrap_or_default();
+        let pool_balance = query_pool_balance(deps.as_ref(), &env, &pool_info.asset_info).unwrap_or_default();
         if pool_balance > pool_info.total_supply.checked_add(Uint128::from(1000000u64)).unwrap() {
             let collect_asset = Asset {
                 info: pool_info.asset_info,
@@ -355,7 +397,8 @@ fn execute_withdraw(
 fn execute_claim(
     deps: DepsMut, 
     env: Env,
-    info: MessageInfo,
+    owner: Addr,
+    recipient: Addr,
     asset_info: AssetInfo, 
 ) -> Result<Response, ContractError> {
     let state: State = STATE.load(deps.storage)?;
@@ -364,7 +407,7 @@ fn execute_claim(
     if current_round.is_none() {
         return Err(ContractError::NotExist {});
     }
-    let mut deposit_info: DepositInfo = DEPOSITS.load(deps.storage, (info.sender.clone(), key.clone()))?;
+    let mut deposit_info: DepositInfo = DEPOSITS.load(deps.storage, (owner.clone(), key.clone()))?;
     let mut reward = Uint128::zero();
 
     let start = if deposit_info.last_claim_round.is_some() {
@@ -378,11 +421,11 @@ fn execute_claim(
         reward = reward + (deposit_info.amount * round.reward_ratio);
     });
     deposit_info.last_claim_round = Some(current_round.unwrap() - 1);
-    DEPOSITS.save(deps.storage, (info.sender.clone(), key.clone()), &deposit_info)?;
+    DEPOSITS.save(deps.storage, (owner, key.clone()), &deposit_info)?;
     let mut msgs = vec![];
 
     if !reward.is_zero() {
-        msgs.push(reward_msg(state.reward_contract, info.sender, reward)?);
+        msgs.push(reward_msg(state.reward_contract, recipient, reward)?);
     }
 
     Ok(Response::new().add_messages(msgs)
@@ -390,6 +433,14 @@ fn execute_claim(
         .add_attribute("reward", reward))
 }
 
+fn execute_set_claim_delegate(deps: DepsMut, info: MessageInfo, delegate: Option<String>) -> Result<Response, ContractError> {
+    let delegate = set_claim_delegate(deps.storage, deps.api, &CLAIM_DELEGATES, info.sender, delegate)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_claim_delegate")
+        .add_attribute("delegate", delegate.map(String::from).unwrap_or_default()))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn execute_settle(
     deps: DepsMut,
     env: Env,
@@ -397,61 +448,316 @@ fn execute_settle(
     player: Addr, 
     input: Uint128, 
     output: Uint128, 
-    asset_info: AssetInfo
+    asset_info: AssetInfo,
+    bet_id: Option<u64>,
 ) -> Result<Response, ContractError> {
     let key = asset_info.clone().to_string();
     let _pool: PoolInfo = POOLS.may_load(deps.storage, key)?.expect("Not Exist Pool");
     // if !GAMES.has(deps.storage, game_contract) {
     //     return Err(ContractError::Unauthorized{}); 
     // }
-    let _game: GameInfo = GAMES.may_load(deps.storage, game_contract)?.expect("Not Allowed Contract");
+    let _game: GameInfo = GAMES.may_load(deps.storage, game_contract.clone())?.expect("Not Allowed Contract");
     let state: State = STATE.load(deps.storage)?;
     let mut msgs: Vec<CosmosMsg> = vec![];
 
-    if !output.is_zero() {
-        let pool_amount = asset_info.query_balance(&deps.querier, env.contract.address.clone())?;
+    // input is already in the pool balance
+    let pool_balance = query_pool_balance(deps.as_ref(), &env, &asset_info)?;
+    let settled = compute_settle(&state, env.block.height, pool_balance, input, output)?;
 
-        let max_output_amount = pool_amount * state.max_output_rate;
-        let mut output_amount = output; 
-        
-        if output_amount > max_output_amount {
-            output_amount = max_output_amount;
+    // the outcome is already known, so the jackpot rolls it on the seed of the next block
+    if let Some(bet_id) = bet_id {
+        if BETS.has(deps.storage, (game_contract.clone(), bet_id)) {
+            return Err(ContractError::AlreadyExist {});
         }
+        BETS.save(deps.storage, (game_contract, bet_id), &BetInfo {
+            player: player.clone(),
+            asset: Asset {
+                info: asset_info.clone(),
+                amount: input,
+            },
+            payout: settled.output,
+            height: env.block.height + 1,
+            placed_height: Some(env.block.height),
+            closed: true,
+            rolled: false,
+        })?;
+    }
 
+    if !settled.output.is_zero() {
         let output_asset = Asset {
             info: asset_info.clone(),
-            amount: output_amount,
+            amount: settled.output,
         };
         msgs.push(output_asset.into_msg(&deps.querier, player.clone())?);
     }
+    if !settled.referral_edge.is_zero() {
+        msgs.push(edge_msg(state.referral_contract.clone(), player.clone(), Asset {
+            info: asset_info.clone(),
+            amount: settled.referral_edge,
+        })?);
+    }
+    msgs.extend(settle_input_msgs(&state, &asset_info, player, &settled)?);
+    
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "settle")
+        .add_attribute("input", input)
+        .add_attribute("output", output)
+        .add_attribute("jackpot", settled.jackpot_amount)
+        .add_attribute("referral_edge", settled.referral_edge)
+        .add_attribute("asset", asset_info.to_string()))
+}
 
-    // 함수로 뺄수 있는지 찾아보기. 없으면 말고 ㅡㅡ..ㅋ Reward Save
-    let current_round = get_round(env.block.height);
-
-    if current_round.is_some() {
-        let share = input;
-        // let share = if asset_info.is_ust() {
-        //     input
-        // } else {
-        //     token_to_ust(&deps.querier, state.terraswap_contract, input)?
-        // };
+// jackpot slice and referral share of a settled wager
+fn settle_input_msgs(state: &State, asset_info: &AssetInfo, player: Addr, settled: &SimulateSettleResponse) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !settled.jackpot_amount.is_zero() {
+        msgs.push(fund_msg(state.jackpot_contract.clone(), Asset {
+            info: asset_info.clone(),
+            amount: settled.jackpot_amount,
+        })?);
+    }
 
+    if !settled.referral_share.is_zero() {
         // UST 가격으로 변환후 
         msgs.push(CosmosMsg::Wasm(WasmMsg::Execute{
                 contract_addr: state.referral_contract.to_string(),
                 funds: vec![],
                 msg: to_binary(&ReferralExecuteMsg::AddShare {
                     address: player,
-                    amount: share,
+                    amount: settled.referral_share,
                 })?
         }));
     }
-    
+    Ok(msgs)
+}
+
+// house balance of the pool without the player accounts
+fn query_pool_balance(deps: Deps, env: &Env, asset_info: &AssetInfo) -> StdResult<Uint128> {
+    let balance = asset_info.query_balance(&deps.querier, env.contract.address.clone())?;
+    let account_total = ACCOUNT_TOTALS.may_load(deps.storage, asset_info.clone().to_string())?.unwrap_or_default();
+    Ok(balance.checked_sub(account_total)?)
+}
+
+fn execute_fund_account(deps: DepsMut, asset: Asset, player: Addr) -> Result<Response, ContractError> {
+    let key = asset.info.clone().to_string();
+    if !POOLS.has(deps.storage, key.clone()) {
+        return Err(ContractError::NotExist {});
+    }
+
+    let balance = ACCOUNTS.update(deps.storage, (player.clone(), key.clone()), |prev| -> StdResult<_> {
+        Ok(prev.unwrap_or_default().checked_add(asset.amount)?)
+    })?;
+    ACCOUNT_TOTALS.update(deps.storage, key, |prev| -> StdResult<_> {
+        Ok(prev.unwrap_or_default().checked_add(asset.amount)?)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "fund_account")
+        .add_attribute("player", player)
+        .add_attribute("amount", asset.amount)
+        .add_attribute("balance", balance))
+}
+
+fn execute_withdraw_account(deps: DepsMut, info: MessageInfo, asset_info: AssetInfo, amount: Option<Uint128>) -> Result<Response, ContractError> {
+    let key = asset_info.clone().to_string();
+    let balance = ACCOUNTS.may_load(deps.storage, (info.sender.clone(), key.clone()))?.unwrap_or_default();
+    let amount = amount.unwrap_or(balance);
+    if amount.is_zero() || amount > balance {
+        return Err(ContractError::Insufficient {});
+    }
+
+    debit_account(deps.storage, &info.sender, &key, amount)?;
+    let msg = Asset {
+        info: asset_info,
+        amount,
+    }.into_msg(&deps.querier, info.sender)?;
+
+    Ok(Response::new().add_message(msg)
+        .add_attribute("method", "withdraw_account")
+        .add_attribute("amount", amount))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_debit_bet(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    player: Addr,
+    asset: Asset,
+    bet_id: u64,
+    payout: Uint128,
+    resolve_height: u64,
+) -> Result<Response, ContractError> {
+    if !GAMES.has(deps.storage, info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    // the seed must not be known when the bet is taken
+    if resolve_height <= env.block.height {
+        return Err(ContractError::InvalidHeight {});
+    }
+    if BETS.has(deps.storage, (info.sender.clone(), bet_id)) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    if asset.amount.is_zero() {
+        return Err(ContractError::Insufficient {});
+    }
+    let key = asset.info.clone().to_string();
+    if !POOLS.has(deps.storage, key.clone()) {
+        return Err(ContractError::NotExist {});
+    }
+    let balance = ACCOUNTS.may_load(deps.storage, (player.clone(), key.clone()))?.unwrap_or_default();
+    if asset.amount > balance {
+        return Err(ContractError::Insufficient {});
+    }
+    let state: State = STATE.load(deps.storage)?;
+
+    // the wager moves from the account into the pool, like the input of a settle
+    debit_account(deps.storage, &player, &key, asset.amount)?;
+    BETS.save(deps.storage, (info.sender, bet_id), &BetInfo {
+        player: player.clone(),
+        asset: asset.clone(),
+        payout,
+        height: resolve_height,
+        placed_height: Some(env.block.height),
+        closed: false,
+        rolled: false,
+    })?;
+    let pool_balance = query_pool_balance(deps.as_ref(), &env, &asset.info)?;
+    let settled = compute_settle(&state, env.block.height, pool_balance, asset.amount, Uint128::zero())?;
+    let msgs = settle_input_msgs(&state, &asset.info, player.clone(), &settled)?;
+
     Ok(Response::new().add_messages(msgs)
-        .add_attribute("method", "settle")
-        .add_attribute("input", input)
+        .add_attribute("method", "debit_bet")
+        .add_attribute("player", player)
+        .add_attribute("bet_id", bet_id.to_string())
+        .add_attribute("input", asset.amount)
+        .add_attribute("jackpot", settled.jackpot_amount)
+        .add_attribute("asset", key))
+}
+
+fn execute_credit_win(deps: DepsMut, env: Env, info: MessageInfo, bet_id: u64, amount: Uint128) -> Result<Response, ContractError> {
+    if !GAMES.has(deps.storage, info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut bet: BetInfo = BETS.may_load(deps.storage, (info.sender.clone(), bet_id))?.ok_or(ContractError::NotExist {})?;
+    if bet.closed {
+        return Err(ContractError::AlreadyExist {});
+    }
+    let key = bet.asset.info.clone().to_string();
+    let state: State = STATE.load(deps.storage)?;
+    let pool_balance = query_pool_balance(deps.as_ref(), &env, &bet.asset.info)?;
+    let output = cap_output(amount.min(bet.payout), pool_balance, state.max_output_rate);
+
+    bet.closed = true;
+    if bet.rolled {
+        BETS.remove(deps.storage, (info.sender, bet_id));
+    } else {
+        BETS.save(deps.storage, (info.sender, bet_id), &bet)?;
+    }
+    if !output.is_zero() {
+        ACCOUNTS.update(deps.storage, (bet.player.clone(), key.clone()), |prev| -> StdResult<_> {
+            Ok(prev.unwrap_or_default().checked_add(output)?)
+        })?;
+        ACCOUNT_TOTALS.update(deps.storage, key.clone(), |prev| -> StdResult<_> {
+            Ok(prev.unwrap_or_default().checked_add(output)?)
+        })?;
+    }
+
+    // the edge of an account bet is only known once it is credited
+    let input = bet.asset.amount;
+    let edge = referral_edge(&state, input, output, jackpot_amount(&state, input));
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !edge.is_zero() {
+        msgs.push(edge_msg(state.referral_contract, bet.player.clone(), Asset {
+            info: bet.asset.info.clone(),
+            amount: edge,
+        })?);
+    }
+
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "credit_win")
+        .add_attribute("player", bet.player)
+        .add_attribute("bet_id", bet_id.to_string())
         .add_attribute("output", output)
-        .add_attribute("asset", asset_info.to_string()))
+        .add_attribute("referral_edge", edge)
+        .add_attribute("asset", key))
+}
+
+// a closed bet is only kept for the jackpot to roll it
+fn execute_remove_bet(deps: DepsMut, info: MessageInfo, game: Addr, bet_id: u64) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.jackpot_contract != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut bet: BetInfo = BETS.may_load(deps.storage, (game.clone(), bet_id))?.ok_or(ContractError::NotExist {})?;
+    if bet.closed {
+        BETS.remove(deps.storage, (game, bet_id));
+    } else {
+        bet.rolled = true;
+        BETS.save(deps.storage, (game, bet_id), &bet)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "remove_bet")
+        .add_attribute("bet_id", bet_id.to_string())
+        .add_attribute("closed", bet.closed.to_string()))
+}
+
+fn debit_account(storage: &mut dyn Storage, player: &Addr, key: &str, amount: Uint128) -> StdResult<()> {
+    ACCOUNTS.update(storage, (player.clone(), key.to_string()), |prev| -> StdResult<_> {
+        Ok(prev.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    ACCOUNT_TOTALS.update(storage, key.to_string(), |prev| -> StdResult<_> {
+        Ok(prev.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    Ok(())
+}
+
+// amounts a settle pays out, shared with the simulate query
+fn compute_settle(state: &State, height: u64, pool_balance: Uint128, input: Uint128, output: Uint128) -> StdResult<SimulateSettleResponse> {
+    let jackpot_amount = jackpot_amount(state, input);
+
+    let output = if output.is_zero() {
+        output
+    } else {
+        let pool_amount = pool_balance.checked_sub(jackpot_amount).map_err(StdError::from)?;
+        cap_output(output, pool_amount, state.max_output_rate)
+    };
+
+    // 함수로 뺄수 있는지 찾아보기. 없으면 말고 ㅡㅡ..ㅋ Reward Save
+    let referral_share = if get_round(height).is_some() {
+        // let share = if asset_info.is_ust() {
+        //     input
+        // } else {
+        //     token_to_ust(&deps.querier, state.terraswap_contract, input)?
+        // };
+        input
+    } else {
+        Uint128::zero()
+    };
+
+    Ok(SimulateSettleResponse {
+        output,
+        referral_share,
+        jackpot_amount,
+        referral_edge: referral_edge(state, input, output, jackpot_amount),
+    })
+}
+
+fn jackpot_amount(state: &State, input: Uint128) -> Uint128 {
+    if state.jackpot_contract.as_str().is_empty() {
+        Uint128::zero()
+    } else {
+        input * state.jackpot_rate
+    }
+}
+
+// net edge of the house after the payout and the jackpot
+fn referral_edge(state: &State, input: Uint128, output: Uint128, jackpot_amount: Uint128) -> Uint128 {
+    if state.referral_contract.as_str().is_empty() {
+        Uint128::zero()
+    } else {
+        input.saturating_sub(output + jackpot_amount) * state.referral_edge_rate
+    }
 }
 
 pub fn execute_add_round(deps: DepsMut, _env: Env, info: MessageInfo, key: Option<String>, round: u64, sender: Addr, reward_amount: Uint128, ) -> Result<Response, ContractError> {
@@ -542,6 +848,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Games {} => to_binary(&query_games(deps, env)?),
         QueryMsg::CurrentRound {} => to_binary(&query_current_round(deps, env)?),
         QueryMsg::RoundInfo { key, round } => to_binary(&query_round_info(deps, env, key, round)?),
+        QueryMsg::SimulateSettle { asset_info, game, input, output } => to_binary(&query_simulate_settle(deps, env, asset_info, game, input, output)?),
+        QueryMsg::Account { asset_info, address } => to_binary(&query_account(deps, asset_info, address)?),
+        QueryMsg::ClaimDelegate { address } => to_binary(&CLAIM_DELEGATES.may_load(deps.storage, address)?),
+        QueryMsg::Bet { game, bet_id } => to_binary(&query_bet(deps, game, bet_id)?),
     }
 }
 
@@ -621,6 +931,24 @@ fn query_current_round(_deps: Deps, env: Env) -> StdResult<Option<u64>> {
     Ok(get_round(env.block.height))
 }
 
+fn query_simulate_settle(deps: Deps, env: Env, asset_info: AssetInfo, game: Addr, input: Uint128, output: Uint128) -> StdResult<SimulateSettleResponse> {
+    POOLS.load(deps.storage, asset_info.clone().to_string())?;
+    GAMES.load(deps.storage, game)?;
+    let state: State = STATE.load(deps.storage)?;
+
+    // on settle the input arrives before the output is paid
+    let pool_balance = query_pool_balance(deps, &env, &asset_info)? + input;
+    compute_settle(&state, env.block.height, pool_balance, input, output)
+}
+
+fn query_bet(deps: Deps, game: Addr, bet_id: u64) -> StdResult<BetInfo> {
+    BETS.load(deps.storage, (game, bet_id))
+}
+
+fn query_account(deps: Deps, asset_info: AssetInfo, address: Addr) -> StdResult<Uint128> {
+    Ok(ACCOUNTS.may_load(deps.storage, (address, asset_info.clone().to_string()))?.unwrap_or_default())
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
@@ -760,3 +1088,320 @@ fn query_current_round(_deps: Deps, env: Env) -> StdResult<Option<u64>> {
 //     //     assert_eq!(42131u64, state.start_block);
 //     // }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{coins, from_binary};
+
+    fn uusd() -> AssetInfo {
+        AssetInfo::NativeToken { denom: "uusd".to_string() }
+    }
+
+    fn uusd_asset(amount: u128) -> Asset {
+        Asset { info: uusd(), amount: Uint128::from(amount) }
+    }
+
+    fn mock_instantiate(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            zerosum_token: Some(Addr::unchecked("zerosum_token")),
+            terraswap_contract: None,
+            collector_contract: None,
+            distributor_contract: None,
+            referral_contract: None,
+            reward_contract: None,
+            max_output_rate: Some(Decimal::percent(10)),
+            jackpot_contract: None,
+            jackpot_rate: None,
+            referral_edge_rate: None,
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps, mock_env(), info, msg).unwrap();
+    }
+
+    fn mock_create_pool(deps: DepsMut) {
+        let info = mock_info("creator", &[]);
+        execute(deps, mock_env(), info, ExecuteMsg::CreatePool { asset: uusd(), swap_contract: None, reward_weight: None }).unwrap();
+    }
+
+    fn mock_add_game(deps: DepsMut) {
+        let info = mock_info("creator", &[]);
+        execute(deps, mock_env(), info, ExecuteMsg::AddGame {
+            name: "game".to_string(),
+            description: "".to_string(),
+            url: "".to_string(),
+            address: Addr::unchecked("game"),
+            creator: Addr::unchecked("creator"),
+        }).unwrap();
+    }
+
+    fn account(deps: Deps, address: &str) -> Uint128 {
+        let res = query(deps, mock_env(), QueryMsg::Account { asset_info: uusd(), address: Addr::unchecked(address) }).unwrap();
+        from_binary(&res).unwrap()
+    }
+
+    #[test]
+    fn account_bets() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_create_pool(deps.as_mut());
+        mock_add_game(deps.as_mut());
+
+        let info = mock_info("player", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundAccount {}).unwrap();
+        // 1000 of the pool and 100 of the player account
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(1100, "uusd"));
+        assert_eq!(Uint128::from(100u64), account(deps.as_ref(), "player"));
+
+        let msg = ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(50),
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12346,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // 계정 잔액보다 큰 베팅
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(150),
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12346,
+        });
+        match res {
+            Err(ContractError::Insufficient {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // 이미 알려진 시드로는 베팅 불가
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(50),
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12345,
+        });
+        match res {
+            Err(ContractError::InvalidHeight {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), msg.clone()).unwrap();
+        assert_eq!(Uint128::from(50u64), account(deps.as_ref(), "player"));
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), msg);
+        match res {
+            Err(ContractError::AlreadyExist {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 2, amount: Uint128::from(10u64) });
+        match res {
+            Err(ContractError::NotExist {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // capped to the payout of the bet, then to 10% of the pool without the accounts (1050)
+        let msg = ExecuteMsg::CreditWin { bet_id: 1, amount: Uint128::from(1000u64) };
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), msg.clone()).unwrap();
+        assert_eq!(Uint128::from(155u64), account(deps.as_ref(), "player"));
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), msg);
+        match res {
+            Err(ContractError::AlreadyExist {}) => {},
+            _ => panic!("must return error"),
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Bet { game: Addr::unchecked("game"), bet_id: 1 }).unwrap();
+        let bet: BetInfo = from_binary(&res).unwrap();
+        assert!(bet.closed);
+
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(100_000, "uusd"));
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(10),
+            bet_id: 2,
+            payout: Uint128::from(20u64),
+            resolve_height: 12346,
+        }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 2, amount: Uint128::from(100u64) }).unwrap();
+        assert_eq!(Uint128::from(165u64), account(deps.as_ref(), "player"));
+    }
+
+    #[test]
+    fn account_bet_edge() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            zerosum_token: Some(Addr::unchecked("zerosum_token")),
+            terraswap_contract: None,
+            collector_contract: None,
+            distributor_contract: None,
+            referral_contract: Some(Addr::unchecked("referral")),
+            reward_contract: None,
+            max_output_rate: Some(Decimal::percent(10)),
+            jackpot_contract: None,
+            jackpot_rate: None,
+            referral_edge_rate: Some(Decimal::percent(50)),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        mock_create_pool(deps.as_mut());
+        mock_add_game(deps.as_mut());
+
+        let info = mock_info("player", &coins(200, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundAccount {}).unwrap();
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(10_200, "uusd"));
+
+        for bet_id in [1, 2] {
+            execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+                player: Addr::unchecked("player"),
+                asset: uusd_asset(100),
+                bet_id,
+                payout: Uint128::from(300u64),
+                resolve_height: 12346,
+            }).unwrap();
+        }
+
+        // 진 베팅은 베팅액 전체가 하우스 엣지
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 1, amount: Uint128::zero() }).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].msg, edge_msg(Addr::unchecked("referral"), Addr::unchecked("player"), uusd_asset(50)).unwrap());
+
+        // 이긴 베팅은 엣지가 없음
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 2, amount: Uint128::from(200u64) }).unwrap();
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn bet_checks() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_create_pool(deps.as_mut());
+        mock_add_game(deps.as_mut());
+
+        let info = mock_info("player", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundAccount {}).unwrap();
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(1100, "uusd"));
+
+        // the player accounts are not part of the pool
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::SimulateSettle {
+            asset_info: uusd(),
+            game: Addr::unchecked("game"),
+            input: Uint128::zero(),
+            output: Uint128::from(1000u64),
+        }).unwrap();
+        let simulated: SimulateSettleResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(100u64), simulated.output);
+        assert_eq!(Uint128::from(1000u64), query_pool_balance(deps.as_ref(), &mock_env(), &uusd()).unwrap());
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(0),
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12346,
+        });
+        match res {
+            Err(ContractError::Insufficient {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: Asset { info: AssetInfo::NativeToken { denom: "ukrw".to_string() }, amount: Uint128::from(10u64) },
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12346,
+        });
+        match res {
+            Err(ContractError::NotExist {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // 이미 지나간 높이
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(10),
+            bet_id: 1,
+            payout: Uint128::from(500u64),
+            resolve_height: 12000,
+        });
+        match res {
+            Err(ContractError::InvalidHeight {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // a bet is still credited after its resolve height, capped to its payout
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+            player: Addr::unchecked("player"),
+            asset: uusd_asset(10),
+            bet_id: 1,
+            payout: Uint128::from(30u64),
+            resolve_height: 12346,
+        }).unwrap();
+        let mut env = mock_env();
+        env.block.height = 12400;
+        let msg = ExecuteMsg::CreditWin { bet_id: 1, amount: Uint128::from(50u64) };
+        execute(deps.as_mut(), env.clone(), mock_info("game", &[]), msg.clone()).unwrap();
+        assert_eq!(Uint128::from(120u64), account(deps.as_ref(), "player"));
+
+        // credited once
+        let res = execute(deps.as_mut(), env, mock_info("game", &[]), msg);
+        match res {
+            Err(ContractError::AlreadyExist {}) => {},
+            _ => panic!("must return error"),
+        }
+        assert_eq!(Uint128::from(120u64), account(deps.as_ref(), "player"));
+    }
+
+    #[test]
+    fn rolled_bets() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_create_pool(deps.as_mut());
+        mock_add_game(deps.as_mut());
+        STATE.update(deps.as_mut().storage, |mut state| -> StdResult<_> {
+            state.jackpot_contract = Addr::unchecked("jackpot");
+            Ok(state)
+        }).unwrap();
+
+        let info = mock_info("player", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundAccount {}).unwrap();
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(1100, "uusd"));
+        for bet_id in 1..=2 {
+            execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::DebitBet {
+                player: Addr::unchecked("player"),
+                asset: uusd_asset(10),
+                bet_id,
+                payout: Uint128::from(20u64),
+                resolve_height: 12346,
+            }).unwrap();
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 1, amount: Uint128::zero() }).unwrap();
+
+        let msg = ExecuteMsg::RemoveBet { game: Addr::unchecked("game"), bet_id: 1 };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), msg.clone());
+        match res {
+            Err(ContractError::Unauthorized {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // a closed bet is dropped right away
+        execute(deps.as_mut(), mock_env(), mock_info("jackpot", &[]), msg).unwrap();
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::Bet { game: Addr::unchecked("game"), bet_id: 1 }).is_err());
+
+        // an open bet waits for its credit
+        let msg = ExecuteMsg::RemoveBet { game: Addr::unchecked("game"), bet_id: 2 };
+        execute(deps.as_mut(), mock_env(), mock_info("jackpot", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Bet { game: Addr::unchecked("game"), bet_id: 2 }).unwrap();
+        let bet: BetInfo = from_binary(&res).unwrap();
+        assert!(bet.rolled && !bet.closed);
+        execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::CreditWin { bet_id: 2, amount: Uint128::from(20u64) }).unwrap();
+        assert_eq!(Uint128::from(100u64), account(deps.as_ref(), "player"));
+        assert!(query(deps.as_ref(), mock_env(), QueryMsg::Bet { game: Addr::unchecked("game"), bet_id: 2 }).is_err());
+    }
+}
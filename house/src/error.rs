@@ -23,6 +23,9 @@ pub enum ContractError {
     
     #[error("Not Enough Token")]
     NotEnoughToken {},
+
+    #[error("Invalid Height")]
+    InvalidHeight {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use zerosum::asset::{AssetInfo};
+use zerosum::house::{GameInfo, BetInfo};
 use zerosum::round::{RoundInfo};
 
 use cosmwasm_std::{Addr, Uint128, Decimal};
@@ -17,15 +18,12 @@ pub struct State {
     pub distributor_contract: Addr,
     pub reward_contract: Addr,
     pub max_output_rate: Decimal,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct GameInfo {
-    pub name: String,
-    pub description: String,
-    pub url: String,
-    pub address: Addr,
-    pub creator: Addr,
+    pub jackpot_contract: Addr,
+    // slice of every wager that feeds the jackpot
+    pub jackpot_rate: Decimal,
+    // slice of the net edge of a settle that goes to the referrers
+    #[serde(default)]
+    pub referral_edge_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -51,4 +49,15 @@ pub const POOLS: Map<String, PoolInfo> = Map::new("pools");
 pub const DEPOSITS: Map<(Addr, String), DepositInfo> = Map::new("deposits");
 
 // pool, round
-pub const ROUNDS: Map<(String, u64), RoundInfo> = Map::new("rounds");
\ No newline at end of file
+pub const ROUNDS: Map<(String, u64), RoundInfo> = Map::new("rounds");
+
+// player, pool
+pub const ACCOUNTS: Map<(Addr, String), Uint128> = Map::new("accounts");
+// pool, sum of the player accounts which is not part of the pool
+pub const ACCOUNT_TOTALS: Map<String, Uint128> = Map::new("account_totals");
+
+// owner, address that can claim for the owner
+pub const CLAIM_DELEGATES: Map<Addr, Addr> = Map::new("claim_delegates");
+
+// game, bet id
+pub const BETS: Map<(Addr, u64), BetInfo> = Map::new("bets");
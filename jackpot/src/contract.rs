@@ -0,0 +1,379 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Addr,
+        Order, CosmosMsg, WasmMsg};
+use cw2::set_contract_version;
+use cw20::{Cw20ReceiveMsg};
+
+use cw_storage_plus::{Bound};
+
+use zerosum::asset::{Asset, AssetInfo, token_asset};
+use zerosum::house::{BetInfo, ExecuteMsg as HouseExecuteMsg, QueryMsg as HouseQueryMsg};
+use zerosum::querier::{query_random};
+use zerosum::jackpot::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, JackpotResponse, WinnerResponse, jackpot_entropy};
+
+use crate::error::ContractError;
+use crate::state::{State, STATE, Jackpot, JACKPOTS, Winner, WINNERS, WINNER_INDEX, ROLLED, next_winner_index};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:jackpot";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = State {
+        gov_contract: info.sender.clone(),
+        house_contract: msg.house_contract.unwrap_or(Addr::unchecked("")),
+        random_contract: msg.random_contract.unwrap_or(Addr::unchecked("")),
+        odds: msg.odds.unwrap_or(1_000_000u32).max(1),
+    };
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    STATE.save(deps.storage, &state)?;
+    WINNER_INDEX.save(deps.storage, &0u64)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("gov_contract", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateState { gov_contract, house_contract, random_contract, odds } =>
+            execute_update_state(deps, info, gov_contract, house_contract, random_contract, odds),
+        ExecuteMsg::Fund {} => {
+            let state: State = STATE.load(deps.storage)?;
+            if info.sender != state.house_contract {
+                return Err(ContractError::Unauthorized {});
+            }
+            let assets: Vec<Asset> = info.funds.into_iter().map(Asset::from).collect();
+            execute_fund(deps, assets, false)
+        },
+        ExecuteMsg::FundReserve {} => {
+            let assets: Vec<Asset> = info.funds.into_iter().map(Asset::from).collect();
+            execute_fund(deps, assets, true)
+        },
+        ExecuteMsg::SetSeedAmount { asset_info, seed_amount } => execute_set_seed_amount(deps, info, asset_info, seed_amount),
+        ExecuteMsg::Award { bet_id } => execute_award(deps, env, info, bet_id),
+    }
+}
+
+fn receive_cw20(deps: DepsMut, _env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    let asset = token_asset(info.sender, cw20_msg.amount);
+    match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::Fund {}) => {
+            let state: State = STATE.load(deps.storage)?;
+            if cw20_msg.sender != state.house_contract.as_str() {
+                return Err(ContractError::Unauthorized {});
+            }
+            execute_fund(deps, vec![asset], false)
+        },
+        Ok(Cw20HookMsg::FundReserve {}) => execute_fund(deps, vec![asset], true),
+        Err(err) => Err(ContractError::Std(err)),
+    }
+}
+
+fn execute_update_state(
+    deps: DepsMut,
+    info: MessageInfo,
+    gov_contract: Option<Addr>,
+    house_contract: Option<Addr>,
+    random_contract: Option<Addr>,
+    odds: Option<u32>,
+) -> Result<Response, ContractError> {
+    STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        if state.gov_contract != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if let Some(gov_contract) = gov_contract {
+            state.gov_contract = gov_contract;
+        }
+        if let Some(house_contract) = house_contract {
+            state.house_contract = house_contract;
+        }
+        if let Some(random_contract) = random_contract {
+            state.random_contract = random_contract;
+        }
+        if let Some(odds) = odds {
+            state.odds = odds.max(1);
+        }
+        Ok(state)
+    })?;
+    Ok(Response::new().add_attribute("method", "update_state"))
+}
+
+fn execute_fund(deps: DepsMut, assets: Vec<Asset>, reserve: bool) -> Result<Response, ContractError> {
+    for asset in assets.iter() {
+        JACKPOTS.update(deps.storage, asset.info.clone().to_string(), |prev| -> Result<_, ContractError> {
+            let mut jackpot = prev.unwrap_or(Jackpot {
+                asset_info: asset.info.clone(),
+                amount: Uint128::zero(),
+                seed_amount: Uint128::zero(),
+                reserve: Uint128::zero(),
+            });
+            if reserve {
+                jackpot.reserve += asset.amount;
+            } else {
+                jackpot.amount += asset.amount;
+            }
+            Ok(jackpot)
+        })?;
+    }
+    let assets: Vec<String> = assets.iter().map(|asset| asset.to_string()).collect();
+    Ok(Response::new()
+        .add_attribute("method", if reserve { "fund_reserve" } else { "fund" })
+        .add_attribute("assets", assets.join(",")))
+}
+
+fn execute_set_seed_amount(deps: DepsMut, info: MessageInfo, asset_info: AssetInfo, seed_amount: Uint128) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.gov_contract != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    JACKPOTS.update(deps.storage, asset_info.clone().to_string(), |prev| -> Result<_, ContractError> {
+        let mut jackpot = prev.unwrap_or(Jackpot {
+            asset_info: asset_info.clone(),
+            amount: Uint128::zero(),
+            seed_amount: Uint128::zero(),
+            reserve: Uint128::zero(),
+        });
+        jackpot.seed_amount = seed_amount;
+        Ok(jackpot)
+    })?;
+    Ok(Response::new()
+        .add_attribute("method", "set_seed_amount")
+        .add_attribute("asset", asset_info.to_string())
+        .add_attribute("seed_amount", seed_amount))
+}
+
+fn execute_award(deps: DepsMut, _env: Env, info: MessageInfo, bet_id: u64) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    // only the house records bets, and only for its games
+    let bet: BetInfo = deps.querier.query_wasm_smart(state.house_contract.to_string(), &HouseQueryMsg::Bet {
+        game: info.sender.clone(),
+        bet_id,
+    }).map_err(|_| ContractError::Unauthorized {})?;
+
+    if ROLLED.has(deps.storage, (info.sender.clone(), bet_id)) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    let mut entropy = bet_id.to_be_bytes().to_vec();
+    entropy.extend(bet.player.as_bytes());
+    let random = query_random(&deps.querier, state.random_contract, bet.height, Some(jackpot_entropy(&entropy)), state.odds - 1, bet.placed_height)?
+        .ok_or(ContractError::NotFed {})?;
+    ROLLED.save(deps.storage, (info.sender.clone(), bet_id), &true)?;
+    // the house keeps the bet only until it is rolled
+    let remove_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: state.house_contract.to_string(),
+        funds: vec![],
+        msg: to_binary(&HouseExecuteMsg::RemoveBet { game: info.sender.clone(), bet_id })?,
+    });
+    if random != 0 {
+        return Ok(Response::new().add_message(remove_msg)
+            .add_attribute("method", "award")
+            .add_attribute("bet_id", bet_id.to_string())
+            .add_attribute("hit", "false"));
+    }
+
+    let player = bet.player;
+    let height = bet.height;
+    let asset_info = bet.asset.info;
+    let key = asset_info.clone().to_string();
+    let mut jackpot: Jackpot = JACKPOTS.may_load(deps.storage, key.clone())?.ok_or(ContractError::NotExist {})?;
+    let prize = Asset {
+        info: asset_info,
+        amount: jackpot.amount,
+    };
+    let seed_amount = jackpot.seed_amount.min(jackpot.reserve);
+    jackpot.reserve -= seed_amount;
+    jackpot.amount = seed_amount;
+    JACKPOTS.save(deps.storage, key, &jackpot)?;
+
+    let winner_idx = next_winner_index(deps.storage)?;
+    WINNERS.save(deps.storage, winner_idx, &Winner {
+        asset: prize.clone(),
+        player: player.clone(),
+        game: info.sender,
+        height,
+    })?;
+
+    let mut msgs = vec![remove_msg];
+    if !prize.amount.is_zero() {
+        msgs.push(prize.clone().into_msg(&deps.querier, player.clone())?);
+    }
+
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "award")
+        .add_attribute("bet_id", bet_id.to_string())
+        .add_attribute("hit", "true")
+        .add_attribute("player", player)
+        .add_attribute("prize", prize.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::Jackpot { asset_info } => to_binary(&query_jackpot(deps, asset_info)?),
+        QueryMsg::Jackpots {} => to_binary(&query_jackpots(deps)?),
+        QueryMsg::Winners { start_after, limit } => to_binary(&query_winners(deps, start_after, limit)?),
+    }
+}
+
+fn query_state(deps: Deps) -> StdResult<State> {
+    let state = STATE.load(deps.storage)?;
+    Ok(state)
+}
+
+fn to_jackpot_response(jackpot: Jackpot) -> JackpotResponse {
+    JackpotResponse {
+        asset: Asset {
+            info: jackpot.asset_info,
+            amount: jackpot.amount,
+        },
+        seed_amount: jackpot.seed_amount,
+        reserve: jackpot.reserve,
+    }
+}
+
+fn query_jackpot(deps: Deps, asset_info: AssetInfo) -> StdResult<JackpotResponse> {
+    let jackpot = JACKPOTS.load(deps.storage, asset_info.to_string())?;
+    Ok(to_jackpot_response(jackpot))
+}
+
+fn query_jackpots(deps: Deps) -> StdResult<Vec<JackpotResponse>> {
+    JACKPOTS.range(deps.storage, None, None, Order::Ascending).map(|item| {
+        let (_, jackpot) = item?;
+        Ok(to_jackpot_response(jackpot))
+    }).collect()
+}
+
+fn query_winners(deps: Deps, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<WinnerResponse>> {
+    let start = start_after.map(|id| Bound::ExclusiveRaw(id.to_be_bytes().to_vec()));
+    WINNERS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (id, winner) = item?;
+        Ok(WinnerResponse {
+            id,
+            asset: winner.asset,
+            player: winner.player,
+            game: winner.game,
+            height: winner.height,
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_binary, ContractResult, SystemResult, SystemError, WasmQuery};
+    use zerosum::random::QueryMsg as RandomQueryMsg;
+
+    fn uusd() -> AssetInfo {
+        AssetInfo::NativeToken { denom: "uusd".to_string() }
+    }
+
+    #[test]
+    fn award() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "house" => {
+                match from_binary(msg).unwrap() {
+                    // bet 1 of the winner, bet 2 of the loser
+                    HouseQueryMsg::Bet { game, bet_id } if game == "game" && bet_id <= 2 => {
+                        let player = if bet_id == 1 { "winner" } else { "loser" };
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&BetInfo {
+                            player: Addr::unchecked(player),
+                            asset: Asset { info: uusd(), amount: Uint128::from(10u64) },
+                            payout: Uint128::from(20u64),
+                            height: 10,
+                            placed_height: Some(9),
+                            closed: true,
+                            rolled: false,
+                        }).unwrap()))
+                    },
+                    _ => SystemResult::Ok(ContractResult::Err("Not Exist".to_string())),
+                }
+            },
+            WasmQuery::Smart { msg, .. } => {
+                let random = match from_binary(msg).unwrap() {
+                    RandomQueryMsg::RandomOne { height: 10, entropy: Some(entropy), .. } if entropy.windows(6).any(|w| w == b"winner") => 0u32,
+                    _ => 1u32,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&Some(random)).unwrap()))
+            },
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let msg = InstantiateMsg {
+            house_contract: Some(Addr::unchecked("house")),
+            random_contract: Some(Addr::unchecked("random")),
+            odds: Some(100),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &coins(100, "uusd")), ExecuteMsg::Fund {});
+        match res {
+            Err(ContractError::Unauthorized {}) => {},
+            _ => panic!("must return error"),
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("house", &coins(1000, "uusd")), ExecuteMsg::Fund {}).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("anyone", &coins(150, "uusd")), ExecuteMsg::FundReserve {}).unwrap();
+        let msg = ExecuteMsg::SetSeedAmount { asset_info: uusd(), seed_amount: Uint128::from(100u64) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        // only bets the house recorded for the calling game
+        let res = execute(deps.as_mut(), mock_env(), mock_info("not_game", &[]), ExecuteMsg::Award { bet_id: 1 });
+        match res {
+            Err(ContractError::Unauthorized {}) => {},
+            _ => panic!("must return error"),
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::Award { bet_id: 3 });
+        match res {
+            Err(ContractError::Unauthorized {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // a miss is rolled once too
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::Award { bet_id: 2 }).unwrap();
+        assert_eq!(1, res.messages.len());
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::Award { bet_id: 2 });
+        match res {
+            Err(ContractError::AlreadyExist {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::Award { bet_id: 1 }).unwrap();
+        assert_eq!(2, res.messages.len());
+
+        // the same bet can not hit twice
+        let res = execute(deps.as_mut(), mock_env(), mock_info("game", &[]), ExecuteMsg::Award { bet_id: 1 });
+        match res {
+            Err(ContractError::AlreadyExist {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Jackpot { asset_info: uusd() }).unwrap();
+        let jackpot: JackpotResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(100u64), jackpot.asset.amount);
+        assert_eq!(Uint128::from(50u64), jackpot.reserve);
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Winners { start_after: None, limit: None }).unwrap();
+        let winners: Vec<WinnerResponse> = from_binary(&res).unwrap();
+        assert_eq!(1, winners.len());
+        assert_eq!(Uint128::from(1000u64), winners[0].asset.amount);
+        assert_eq!(Addr::unchecked("winner"), winners[0].player);
+        assert_eq!(10, winners[0].height);
+    }
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Already Exist")]
+    AlreadyExist {},
+
+    #[error("Not Exist")]
+    NotExist {},
+
+    #[error("Not Fed Seed")]
+    NotFed {},
+}
@@ -0,0 +1,50 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use zerosum::asset::{Asset, AssetInfo};
+
+use cosmwasm_std::{Addr, Uint128, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::error::ContractError;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct State {
+    pub gov_contract: Addr,
+    pub house_contract: Addr,
+    pub random_contract: Addr,
+    // one in `odds` bets hits the jackpot
+    pub odds: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Jackpot {
+    pub asset_info: AssetInfo,
+    pub amount: Uint128,
+    pub seed_amount: Uint128,
+    pub reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Winner {
+    pub asset: Asset,
+    pub player: Addr,
+    pub game: Addr,
+    pub height: u64,
+}
+
+pub fn next_winner_index(store: &mut dyn Storage) -> Result<u64, ContractError> {
+    WINNER_INDEX.update(store, |prev| -> Result<u64, ContractError> {
+        Ok(prev + 1)
+    })
+}
+
+pub const STATE: Item<State> = Item::new("state");
+// asset, jackpot
+pub const JACKPOTS: Map<String, Jackpot> = Map::new("jackpots");
+
+pub const WINNER_INDEX: Item<u64> = Item::new("winner_index");
+pub const WINNERS: Map<u64, Winner> = Map::new("winners");
+
+// game, bet id of the bets that already rolled, hit or not
+pub const ROLLED: Map<(Addr, u64), bool> = Map::new("rolled");
@@ -0,0 +1,80 @@
+use cosmwasm_std::{Addr, Api, StdResult, Storage};
+use cw_storage_plus::Map;
+
+// the owner claims to the recipient, its delegate claims to the owner.
+// `delegates` maps an owner to the address that can claim for it, None when the sender can not claim
+pub fn claim_accounts(
+    storage: &dyn Storage,
+    api: &dyn Api,
+    delegates: &Map<Addr, Addr>,
+    sender: &Addr,
+    owner: Option<String>,
+    recipient: Option<String>,
+) -> StdResult<Option<(Addr, Addr)>> {
+    let owner = match owner {
+        Some(owner) => api.addr_validate(&owner)?,
+        None => sender.clone(),
+    };
+    let recipient = match recipient {
+        Some(recipient) => api.addr_validate(&recipient)?,
+        None => owner.clone(),
+    };
+    if owner != *sender
+        && (delegates.may_load(storage, owner.clone())?.as_ref() != Some(sender) || recipient != owner) {
+        return Ok(None);
+    }
+    Ok(Some((owner, recipient)))
+}
+
+// sets the delegate of the owner, or clears it without one
+pub fn set_claim_delegate(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    delegates: &Map<Addr, Addr>,
+    owner: Addr,
+    delegate: Option<String>,
+) -> StdResult<Option<Addr>> {
+    match delegate {
+        Some(delegate) => {
+            let delegate = api.addr_validate(&delegate)?;
+            delegates.save(storage, owner, &delegate)?;
+            Ok(Some(delegate))
+        },
+        None => {
+            delegates.remove(storage, owner);
+            Ok(None)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{MockApi, MockStorage};
+
+    const DELEGATES: Map<Addr, Addr> = Map::new("claim_delegates");
+
+    #[test]
+    fn delegated_claims() {
+        let mut storage = MockStorage::new();
+        let api = MockApi::default();
+        let owner = Addr::unchecked("owner");
+        let partner = Addr::unchecked("partner");
+
+        let accounts = claim_accounts(&storage, &api, &DELEGATES, &owner, None, Some("wallet".to_string())).unwrap();
+        assert_eq!(accounts, Some((owner.clone(), Addr::unchecked("wallet"))));
+        let accounts = claim_accounts(&storage, &api, &DELEGATES, &partner, Some("owner".to_string()), None).unwrap();
+        assert_eq!(accounts, None);
+
+        // the delegate only claims to the owner
+        set_claim_delegate(&mut storage, &api, &DELEGATES, owner.clone(), Some("partner".to_string())).unwrap();
+        let accounts = claim_accounts(&storage, &api, &DELEGATES, &partner, Some("owner".to_string()), None).unwrap();
+        assert_eq!(accounts, Some((owner.clone(), owner.clone())));
+        let accounts = claim_accounts(&storage, &api, &DELEGATES, &partner, Some("owner".to_string()), Some("partner".to_string())).unwrap();
+        assert_eq!(accounts, None);
+
+        set_claim_delegate(&mut storage, &api, &DELEGATES, owner, None).unwrap();
+        let accounts = claim_accounts(&storage, &api, &DELEGATES, &partner, Some("owner".to_string()), None).unwrap();
+        assert_eq!(accounts, None);
+    }
+}
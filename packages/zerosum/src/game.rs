@@ -0,0 +1,340 @@
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, Decimal, Order, QuerierWrapper, StdResult, Storage, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::asset::{Asset, AssetInfo};
+use crate::house::{Cw20HookMsg as HouseCw20HookMsg, ExecuteMsg as HouseExecuteMsg};
+use crate::querier::query_random;
+use crate::random::RandomnessCallback;
+
+/// ## Description
+/// Standard messages of a game contract. `T` is the game specific bet, e.g. the number picked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg<T> {
+    Receive(Cw20ReceiveMsg),
+    PlaceBet { bet: T },
+    Resolve { bet_id: u64 },
+    Refund { bet_id: u64 },
+    ReceiveRandomness(RandomnessCallback),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg<T> {
+    PlaceBet { bet: T },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg<T> {
+    PendingBet { bet_id: u64 },
+    PendingBets { start_after: Option<u64>, limit: Option<u64> },
+    SimulateBet { input: Asset, bet: T },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateBetResponse {
+    pub win_probability: Decimal,
+    pub multiplier: Decimal,
+    // output of a winning bet after the house edge
+    pub payout: Uint128,
+    // expected output of the bet, `win_probability * payout`
+    pub expected_value: Uint128,
+}
+
+/// ## Description
+/// A bet that waits for the seed of `resolve_height`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBet<T> {
+    pub id: u64,
+    pub player: Addr,
+    pub asset: Asset,
+    pub bet: T,
+    pub placed_height: u64,
+    pub resolve_height: u64,
+}
+
+impl<T> PendingBet<T> {
+    /// ## Description
+    /// Entropy mixed into the seed, unique per bet so bets on the same height resolve independently.
+    pub fn entropy(&self) -> Vec<u8> {
+        let mut entropy = self.id.to_be_bytes().to_vec();
+        entropy.extend(self.player.as_bytes());
+        entropy
+    }
+
+    /// ## Description
+    /// Returns true when the bet was not resolved within `timeout` blocks after its resolve height
+    /// and can be refunded.
+    pub fn is_expired(&self, height: u64, timeout: u64) -> bool {
+        height > self.resolve_height.saturating_add(timeout)
+    }
+
+    /// ## Description
+    /// Returns a random number between 0 and `max_value` (inclusive) for the bet,
+    /// or [`None`] while the seed of the resolve height is not fed yet.
+    /// Seed contributions committed after the bet was placed are left out.
+    pub fn resolve(&self, querier: &QuerierWrapper, random_contract: Addr, max_value: u32) -> StdResult<Option<u32>> {
+        query_random(querier, random_contract, self.resolve_height, Some(self.entropy()), max_value, Some(self.placed_height))
+    }
+
+    /// ## Description
+    /// Returns the message sending the wager back to the player.
+    pub fn refund_msg(&self, querier: &QuerierWrapper) -> StdResult<CosmosMsg> {
+        self.asset.clone().into_msg(querier, self.player.clone())
+    }
+}
+
+/// ## Description
+/// Storage of pending bets keyed by an increasing bet id.
+pub struct PendingBets<'a, T> {
+    bets: Map<'a, u64, PendingBet<T>>,
+    last_id: Item<'a, u64>,
+}
+
+impl<'a, T> PendingBets<'a, T> {
+    pub const fn new(bets_namespace: &'a str, last_id_namespace: &'a str) -> Self {
+        PendingBets {
+            bets: Map::new(bets_namespace),
+            last_id: Item::new(last_id_namespace),
+        }
+    }
+}
+
+impl<'a, T> PendingBets<'a, T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn place(
+        &self,
+        store: &mut dyn Storage,
+        player: Addr,
+        asset: Asset,
+        bet: T,
+        placed_height: u64,
+        resolve_height: u64,
+    ) -> StdResult<PendingBet<T>> {
+        let id = self.last_id.may_load(store)?.unwrap_or_default() + 1;
+        self.last_id.save(store, &id)?;
+        let pending_bet = PendingBet {
+            id,
+            player,
+            asset,
+            bet,
+            placed_height,
+            resolve_height,
+        };
+        self.bets.save(store, id, &pending_bet)?;
+        Ok(pending_bet)
+    }
+
+    pub fn load(&self, store: &dyn Storage, id: u64) -> StdResult<PendingBet<T>> {
+        self.bets.load(store, id)
+    }
+
+    pub fn may_load(&self, store: &dyn Storage, id: u64) -> StdResult<Option<PendingBet<T>>> {
+        self.bets.may_load(store, id)
+    }
+
+    pub fn remove(&self, store: &mut dyn Storage, id: u64) {
+        self.bets.remove(store, id)
+    }
+
+    pub fn range(&self, store: &dyn Storage, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<PendingBet<T>>> {
+        let start = start_after.map(|id| Bound::ExclusiveRaw(id.to_be_bytes().to_vec()));
+        self.bets.range(store, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+            let (_, pending_bet) = item?;
+            Ok(pending_bet)
+        }).collect()
+    }
+}
+
+/// ## Description
+/// Returns the message settling a bet with the house. The wager goes along with it.
+/// A `bet_id` lets the jackpot roll the bet later.
+pub fn settle_msg(house_contract: Addr, player: Addr, input: Asset, output: Uint128, bet_id: Option<u64>) -> StdResult<CosmosMsg> {
+    match input.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: house_contract.to_string(),
+            msg: to_binary(&HouseExecuteMsg::Settle { player, output, bet_id })?,
+            funds: vec![Coin { denom, amount: input.amount }],
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: house_contract.to_string(),
+                amount: input.amount,
+                msg: to_binary(&HouseCw20HookMsg::Settle { player, output, bet_id })?,
+            })?,
+            funds: vec![],
+        })),
+    }
+}
+
+/// ## Description
+/// Returns the message taking a wager from the player's house account for the bet `bet_id`.
+pub fn debit_bet_msg(house_contract: Addr, player: Addr, asset: Asset, bet_id: u64, payout: Uint128, resolve_height: u64) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: house_contract.to_string(),
+        msg: to_binary(&HouseExecuteMsg::DebitBet { player, asset, bet_id, payout, resolve_height })?,
+        funds: vec![],
+    }))
+}
+
+/// ## Description
+/// Returns the message closing the bet `bet_id`, crediting `amount` to the player's house account.
+pub fn credit_win_msg(house_contract: Addr, bet_id: u64, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: house_contract.to_string(),
+        msg: to_binary(&HouseExecuteMsg::CreditWin { bet_id, amount })?,
+        funds: vec![],
+    }))
+}
+
+/// ## Description
+/// Returns the payout of a winning bet, `input * multiplier` less the house edge.
+pub fn payout(input: Uint128, multiplier: Decimal, house_edge: Decimal) -> Uint128 {
+    if house_edge >= Decimal::one() {
+        return Uint128::zero();
+    }
+    input * multiplier * (Decimal::one() - house_edge)
+}
+
+/// ## Description
+/// Returns the [`SimulateBetResponse`] of a bet winning `multiplier` times the input with `win_probability`.
+pub fn simulate_bet(input: Uint128, win_probability: Decimal, multiplier: Decimal, house_edge: Decimal) -> SimulateBetResponse {
+    let payout = payout(input, multiplier, house_edge);
+    SimulateBetResponse {
+        win_probability,
+        multiplier,
+        payout,
+        expected_value: payout * win_probability,
+    }
+}
+
+/// ## Description
+/// Caps the output to `max_output_rate` of the pool, the same rule the house applies on settle.
+pub fn cap_output(output: Uint128, pool_amount: Uint128, max_output_rate: Decimal) -> Uint128 {
+    let max_output_amount = pool_amount * max_output_rate;
+    if output > max_output_amount {
+        max_output_amount
+    } else {
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_binary;
+    use cosmwasm_std::testing::MockStorage;
+
+    fn uusd(amount: u128) -> Asset {
+        Asset {
+            info: AssetInfo::NativeToken { denom: "uusd".to_string() },
+            amount: Uint128::from(amount),
+        }
+    }
+
+    #[test]
+    fn pending_bets() {
+        let mut storage = MockStorage::new();
+        let bets: PendingBets<u8> = PendingBets::new("bets", "last_id");
+
+        let first = bets.place(&mut storage, Addr::unchecked("player1"), uusd(100), 1u8, 10, 12).unwrap();
+        let second = bets.place(&mut storage, Addr::unchecked("player2"), uusd(200), 2u8, 11, 13).unwrap();
+        assert_eq!(1, first.id);
+        assert_eq!(2, second.id);
+        assert_eq!(first, bets.load(&storage, 1).unwrap());
+
+        let page = bets.range(&storage, Some(1), None).unwrap();
+        assert_eq!(vec![second.clone()], page);
+        let page = bets.range(&storage, None, Some(1)).unwrap();
+        assert_eq!(vec![first.clone()], page);
+
+        // ids are not reused after a remove
+        bets.remove(&mut storage, 2);
+        assert_eq!(None, bets.may_load(&storage, 2).unwrap());
+        let third = bets.place(&mut storage, Addr::unchecked("player1"), uusd(100), 3u8, 12, 14).unwrap();
+        assert_eq!(3, third.id);
+
+        // the same player on the same height still gets another entropy
+        assert_ne!(first.entropy(), third.entropy());
+        assert!(!first.is_expired(22, 10));
+        assert!(first.is_expired(23, 10));
+    }
+
+    #[test]
+    fn settle_msgs() {
+        let msg = settle_msg(Addr::unchecked("house"), Addr::unchecked("player"), uusd(100), Uint128::from(150u64), None).unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+                assert_eq!("house", contract_addr);
+                assert_eq!(vec![Coin { denom: "uusd".to_string(), amount: Uint128::from(100u64) }], funds);
+                assert_eq!(HouseExecuteMsg::Settle {
+                    player: Addr::unchecked("player"),
+                    output: Uint128::from(150u64),
+                    bet_id: None,
+                }, from_binary(&msg).unwrap());
+            },
+            _ => panic!("must be a wasm execute"),
+        }
+
+        let input = Asset {
+            info: AssetInfo::Token { contract_addr: Addr::unchecked("token") },
+            amount: Uint128::from(100u64),
+        };
+        let msg = settle_msg(Addr::unchecked("house"), Addr::unchecked("player"), input, Uint128::zero(), Some(7)).unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, funds }) => {
+                assert_eq!("token", contract_addr);
+                assert!(funds.is_empty());
+                assert_eq!(Cw20ExecuteMsg::Send {
+                    contract: "house".to_string(),
+                    amount: Uint128::from(100u64),
+                    msg: to_binary(&HouseCw20HookMsg::Settle {
+                        player: Addr::unchecked("player"),
+                        output: Uint128::zero(),
+                        bet_id: Some(7),
+                    }).unwrap(),
+                }, from_binary(&msg).unwrap());
+            },
+            _ => panic!("must be a wasm execute"),
+        }
+    }
+
+    #[test]
+    fn payouts() {
+        let multiplier = Decimal::percent(150);
+        assert_eq!(Uint128::from(148u64), payout(Uint128::from(100u64), multiplier, Decimal::percent(1)));
+        // rounded down on the multiplier and again on the edge, 3 * 1.5 = 4, 4 * 0.99 = 3
+        assert_eq!(Uint128::from(3u64), payout(Uint128::from(3u64), multiplier, Decimal::percent(1)));
+        assert_eq!(Uint128::zero(), payout(Uint128::from(100u64), multiplier, Decimal::one()));
+        assert_eq!(Uint128::zero(), payout(Uint128::from(100u64), multiplier, Decimal::percent(150)));
+
+        let res = simulate_bet(Uint128::from(100u64), Decimal::percent(33), Decimal::percent(300), Decimal::percent(1));
+        assert_eq!(SimulateBetResponse {
+            win_probability: Decimal::percent(33),
+            multiplier: Decimal::percent(300),
+            payout: Uint128::from(297u64),
+            // 297 * 0.33 = 98.01
+            expected_value: Uint128::from(98u64),
+        }, res);
+    }
+
+    #[test]
+    fn cap_outputs() {
+        let rate = Decimal::percent(10);
+        assert_eq!(Uint128::from(100u64), cap_output(Uint128::from(100u64), Uint128::from(1000u64), rate));
+        assert_eq!(Uint128::from(100u64), cap_output(Uint128::from(101u64), Uint128::from(1000u64), rate));
+        assert_eq!(Uint128::from(99u64), cap_output(Uint128::from(99u64), Uint128::from(1000u64), rate));
+        // 999 * 0.1 = 99.9
+        assert_eq!(Uint128::from(99u64), cap_output(Uint128::from(100u64), Uint128::from(999u64), rate));
+        assert_eq!(Uint128::zero(), cap_output(Uint128::from(100u64), Uint128::zero(), rate));
+    }
+}
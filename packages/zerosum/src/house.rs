@@ -15,6 +15,9 @@ pub struct InstantiateMsg {
     pub referral_contract: Option<Addr>,
     pub reward_contract: Option<Addr>,
     pub max_output_rate: Option<Decimal>,
+    pub jackpot_contract: Option<Addr>,
+    pub jackpot_rate: Option<Decimal>,
+    pub referral_edge_rate: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -30,6 +33,9 @@ pub enum ExecuteMsg {
         referral_contract: Option<Addr>,
         reward_contract: Option<Addr>,
         max_output_rate: Option<Decimal>,
+        jackpot_contract: Option<Addr>,
+        jackpot_rate: Option<Decimal>,
+        referral_edge_rate: Option<Decimal>,
     },
     CreatePool {
         asset: AssetInfo,
@@ -42,9 +48,11 @@ pub enum ExecuteMsg {
         reward_weight: Option<Decimal>,
     },
     Deposit {},
+    // `bet_id` records the bet so the jackpot can roll it
     Settle {
         player: Addr,
         output: Uint128,
+        bet_id: Option<u64>,
     },
     AddGame {
         name: String,
@@ -59,10 +67,40 @@ pub enum ExecuteMsg {
     Withdraw {
         asset_info: AssetInfo,
     },
+    // `owner` claimed by its delegate, which always pays the owner
     Claim {
         asset_info: AssetInfo,
+        owner: Option<String>,
+        recipient: Option<String>,
     },
+    // address that can claim for the sender, None removes it
+    SetClaimDelegate { delegate: Option<String> },
     Collect {},
+    // player balance games bet from without a transfer per bet
+    FundAccount {},
+    WithdrawAccount {
+        asset_info: AssetInfo,
+        amount: Option<Uint128>,
+    },
+    // only from GAMES, opens the bet `bet_id` of the game paying at most `payout`,
+    // resolved on the seed of the future `resolve_height`
+    DebitBet {
+        player: Addr,
+        asset: Asset,
+        bet_id: u64,
+        payout: Uint128,
+        resolve_height: u64,
+    },
+    // only from GAMES, closes an open bet, zero for a lost bet
+    CreditWin {
+        bet_id: u64,
+        amount: Uint128,
+    },
+    // only from the jackpot once it rolled the bet, drops the bet when it is closed
+    RemoveBet {
+        game: Addr,
+        bet_id: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -72,12 +110,13 @@ pub enum Cw20HookMsg {
     Settle {
         player: Addr,
         output: Uint128,
+        bet_id: Option<u64>,
     },
     AddRound {
         key: Option<String>,
         round: u64,
     },
-    
+    FundAccount {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -92,6 +131,10 @@ pub enum QueryMsg {
     Games {},
     CurrentRound {},
     RoundInfo { key: String, round: u64  },
+    SimulateSettle { asset_info: AssetInfo, game: Addr, input: Uint128, output: Uint128 },
+    Account { asset_info: AssetInfo, address: Addr },
+    ClaimDelegate { address: Addr },
+    Bet { game: Addr, bet_id: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -99,4 +142,46 @@ pub struct PoolResponse {
     pub name: String,
     pub asset: Asset,
     pub total_supply: Uint128,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSettleResponse {
+    // output the player receives after the max output cap
+    pub output: Uint128,
+    // share credited to the player's referral
+    pub referral_share: Uint128,
+    // slice of the input sent to the jackpot
+    pub jackpot_amount: Uint128,
+    // slice of the net edge paid to the referrers in the wagered asset
+    #[serde(default)]
+    pub referral_edge: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GameInfo {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub address: Addr,
+    pub creator: Addr,
+}
+
+/// ## Description
+/// A wager a game debited from a player account or settled with a bet id.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BetInfo {
+    pub player: Addr,
+    pub asset: Asset,
+    // most the bet can be credited
+    pub payout: Uint128,
+    // seed height the jackpot rolls the bet on
+    pub height: u64,
+    // height the bet was taken at, None for the bets recorded before it was kept
+    #[serde(default)]
+    pub placed_height: Option<u64>,
+    pub closed: bool,
+    // the jackpot rolled it, an open bet is dropped once credited
+    #[serde(default)]
+    pub rolled: bool,
+}
+
@@ -0,0 +1,96 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, StdResult, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::asset::{Asset, AssetInfo};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub house_contract: Option<Addr>,
+    pub random_contract: Option<Addr>,
+    pub odds: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateState {
+        gov_contract: Option<Addr>,
+        house_contract: Option<Addr>,
+        random_contract: Option<Addr>,
+        odds: Option<u32>,
+    },
+    // slice of a wager, only from the house
+    Fund {},
+    // reserve the jackpot is seeded from when it resets
+    FundReserve {},
+    SetSeedAmount {
+        asset_info: AssetInfo,
+        seed_amount: Uint128,
+    },
+    // called by a game to roll one of its bets recorded by the house, once per bet
+    Award {
+        bet_id: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Fund {},
+    FundReserve {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    State {},
+    Jackpot { asset_info: AssetInfo },
+    Jackpots {},
+    Winners { start_after: Option<u64>, limit: Option<u64> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JackpotResponse {
+    pub asset: Asset,
+    pub seed_amount: Uint128,
+    pub reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerResponse {
+    pub id: u64,
+    pub asset: Asset,
+    pub player: Addr,
+    pub game: Addr,
+    pub height: u64,
+}
+
+/// Entropy the jackpot draw is made with, kept apart from the game outcome of the same bet
+pub fn jackpot_entropy(entropy: &[u8]) -> Vec<u8> {
+    let mut jackpot_entropy = entropy.to_vec();
+    jackpot_entropy.extend(b"jackpot");
+    jackpot_entropy
+}
+
+pub fn fund_msg(contract: Addr, asset: Asset) -> StdResult<CosmosMsg> {
+    match asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_binary(&ExecuteMsg::Fund {})?,
+            funds: vec![Coin { denom, amount: asset.amount }],
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: contract.to_string(),
+                amount: asset.amount,
+                msg: to_binary(&Cw20HookMsg::Fund {})?,
+            })?,
+            funds: vec![],
+        })),
+    }
+}
@@ -7,4 +7,8 @@ pub mod referral;
 pub mod terraswap;
 pub mod round;
 pub mod collector;
-pub mod reward;
\ No newline at end of file
+pub mod reward;
+pub mod prize_pool;
+pub mod jackpot;
+pub mod game;
+pub mod claim;
@@ -0,0 +1,80 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::asset::AssetInfo;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub asset_info: AssetInfo,
+    pub zerosum_token: Option<Addr>,
+    pub house_contract: Option<Addr>,
+    pub random_contract: Option<Addr>,
+    pub period_blocks: Option<u64>,
+    pub draw_delay: Option<u64>,
+    pub winner_count: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    UpdateState {
+        gov_contract: Option<Addr>,
+        zerosum_token: Option<Addr>,
+        house_contract: Option<Addr>,
+        random_contract: Option<Addr>,
+        draw_delay: Option<u64>,
+        winner_count: Option<u32>,
+    },
+    Deposit {},
+    Withdraw { amount: Option<Uint128> },
+    // claim the house rewards for the oldest undrawn period and fix the draw height
+    StartDraw {},
+    // pick the winners once the seed of the draw height is fed, a batch of depositors per call
+    // until the draw is saved
+    CompleteDraw {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Deposit {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    State {},
+    CurrentPeriod {},
+    Depositor { address: Addr },
+    Depositors { start_after: Option<Addr>, limit: Option<u64> },
+    PendingDraw {},
+    Draw { period: u64 },
+    Draws { start_after: Option<u64>, limit: Option<u64> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositorResponse {
+    pub address: Addr,
+    pub amount: Uint128,
+    // time-weighted balance accumulated in the current period so far
+    pub weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeriodResponse {
+    pub period: u64,
+    pub start_height: u64,
+    pub end_height: u64,
+    pub total_amount: Uint128,
+    pub total_weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WinnerInfo {
+    pub address: Addr,
+    pub amount: Uint128,
+}
@@ -148,7 +148,8 @@ pub fn query_random(
     random_contract: Addr, 
     height: u64, 
     entropy: Option<Vec<u8>>, 
-    max_value: u32
+    max_value: u32,
+    placed_height: Option<u64>,
 ) -> StdResult<Option<u32>> {
     let res: Option<u32> = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
         contract_addr: String::from(random_contract),
@@ -156,11 +157,25 @@ pub fn query_random(
             height: height,
             entropy: entropy,
             max_value: max_value,
+            max_lag: None,
+            placed_height,
         })?,
     }))?;
     Ok(res)
 }
 
+/// ## Description
+/// Returns the hash of the block at `height` through the tendermint custom query.
+/// The query goes to the raw querier so it works from contracts using the default [`QuerierWrapper`].
+pub fn query_block_hash(querier: &QuerierWrapper, height: u64) -> StdResult<String> {
+    let tendermint_querier: QuerierWrapper<TendermintQueryWrapper> = QuerierWrapper::new(&**querier);
+    let res: BlockInfoResposne = tendermint_querier.query(&QueryRequest::Custom(TendermintQueryWrapper {
+        route: TendermintRoute::Tendermint,
+        query_data: TendermintQuery::BlockInfo { height },
+    }))?;
+    Ok(res.block_id.hash)
+}
+
 pub fn token_to_ust(
     querier: &QuerierWrapper,
     swap_contract: Addr,
@@ -1,19 +1,80 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr};
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomMode {
+    Feeder,
+    Drand,
+    // hash of both, neither the feeders nor drand alone decide the seed
+    Mixed,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    UpdateState { gov_contract: Addr },
+    Receive(Cw20ReceiveMsg),
+    // `block_entropy` mixes the hash of the target block into the seed
+    UpdateState { gov_contract: Option<Addr>, quorum: Option<u32>, mode: Option<RandomMode>, block_entropy: Option<bool> },
     AddFeeder { address: Addr },
     RemoveFeeder { address: Addr },
+    // queued requests are not dispatched by it, DispatchRequests sends them
     Feed { height: Option<u64>, seed: String },
+    // sha256 of the contribution, before the height
+    CommitSeed { height: u64, hash: Binary },
+    // at or after the height
+    RevealSeed { height: u64, seed: String },
+    // anyone, once the reveal window of the height closed with the quorum revealed.
+    // the commitments still unrevealed are left out of the seed
+    FinalizeSeed { height: u64 },
+    // gov only. a compressed G1 group key, a genesis time not after the block and a nonzero period
+    UpdateDrand { pubkey: Binary, genesis_time: u64, period: u64 },
+    // anyone can relay a beacon, it is verified against the drand public key
+    AddBeacon { round: u64, previous_signature: Binary, signature: Binary },
+    // the requester gets a `ReceiveRandomness` callback once the seed of the height is final,
+    // sent by DispatchRequests. the request height is its placed height
+    RequestRandomness {
+        request_id: u64,
+        resolve_height: u64,
+        entropy: Option<Vec<u8>>,
+        callback_msg: Binary,
+    },
+    // anyone, sends the callbacks whose seed is final. the only place they are sent,
+    // each call goes on scanning the queue from where the last one stopped
+    DispatchRequests {},
+    RetryRequest { id: u64 },
+    UpdateStaking {
+        zerosum_token: Option<Addr>,
+        reward_contract: Option<Addr>,
+        collector_contract: Option<Addr>,
+        min_bond: Option<Uint128>,
+        // paid to the contributors of the assigned heights once their seed is final
+        feed_reward: Option<Uint128>,
+        reveal_window: Option<u64>,
+        slash_rate: Option<Decimal>,
+        feed_interval: Option<u64>,
+    },
+    // locked while a commitment is open or a skipped assigned height can still be slashed
+    Unbond { amount: Option<Uint128> },
+    // anyone can report an unrevealed commitment, or an assigned height skipped
+    // within twice the reveal window
+    SlashMissed { height: u64, feeder: Addr },
+    // drops the seeds below the height, keeping the ones open requests and bets still wait for
+    // and the heights still slashable. a pruned height can not be slashed as skipped
+    PruneSeeds { before_height: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    Bond {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -21,15 +82,169 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     // GetCount returns the current count as a json-encoded number
     Seed { height: u64 },
+    // the seed fed for exactly the height
+    SeedAt { height: u64 },
+    Seeds { start_after: Option<u64>, limit: Option<u64> },
+    // `placed_height` is the height the outcome was bet on, contributions committed at or after it
+    // are left out of the seed, which has no value until the earlier ones alone meet the quorum
     RandomOne { 
         height: u64,
         entropy: Option<Vec<u8>>,
         max_value: u32,
+        // fails when the seed comes from more than `max_lag` blocks after the height, as a drand
+        // checkpoint can. committed seeds are only ever taken from the height itself
+        max_lag: Option<u64>,
+        placed_height: Option<u64>,
     },
     RandomBetween { 
         height: u64,
         entropy: Option<Vec<u8>>,
         min_value: u32,
         max_value: u32,
+        placed_height: Option<u64>,
     },
+    RandomMany {
+        height: u64,
+        entropy: Option<Vec<u8>>,
+        count: u32,
+        min_value: u32,
+        max_value: u32,
+        placed_height: Option<u64>,
+    },
+    // the numbers below `n` in random order
+    Shuffle {
+        height: u64,
+        entropy: Option<Vec<u8>>,
+        n: u32,
+        placed_height: Option<u64>,
+    },
+    // index of `weights` picked with a chance proportional to its weight
+    WeightedPick {
+        height: u64,
+        entropy: Option<Vec<u8>>,
+        weights: Vec<u32>,
+        placed_height: Option<u64>,
+    },
+    // everything needed to recompute a `RandomBetween` outcome offline
+    VerifyOutcome {
+        height: u64,
+        entropy: Option<Vec<u8>>,
+        min: u32,
+        max: u32,
+        placed_height: Option<u64>,
+    },
+    Commitment { height: u64 },
+    Beacon { round: u64 },
+    // drand round the seed of the height is taken from
+    DrandRound { height: u64 },
+    Request { id: u64 },
+    Feeder { address: Addr },
+    Feeders { start_after: Option<Addr>, limit: Option<u64> },
+    PendingRequests { start_after: Option<u64>, limit: Option<u64> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeedResponse {
+    pub height: u64,
+    pub seed: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CommitmentResponse {
+    pub height: u64,
+    pub committed: Vec<Addr>,
+    // feeders whose contribution is revealed
+    pub contributors: Vec<Addr>,
+    // feeders that did not reveal a contribution yet
+    pub missing: Vec<Addr>,
+    pub quorum: u32,
+    pub finalized: bool,
+    // revealed contributions in feeder order, the seed is the sha256 of them concatenated
+    pub contributions: Vec<ContributionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributionResponse {
+    pub feeder: Addr,
+    // sha256 of the seed, committed before the height
+    pub hash: Binary,
+    pub seed: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifyOutcomeResponse {
+    pub height: u64,
+    // seed the draws are made from
+    pub seed: String,
+    // round of the feeder contributions, with the contribution of each feeder
+    pub seed_height: Option<u64>,
+    pub contributions: Vec<ContributionResponse>,
+    // the beacon of the round is mixed after the feeder seed
+    pub drand_round: Option<u64>,
+    pub drand_randomness: Option<Binary>,
+    pub block_hash: Option<String>,
+    // height followed by the entropy of the caller
+    pub entropy: Binary,
+    // sha256 of the seed and entropy, the key of the ChaCha stream
+    pub chacha_seed: Binary,
+    pub value: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeederResponse {
+    pub address: Addr,
+    pub bond: Uint128,
+    pub last_fed_height: u64,
+    pub missed: u64,
+    // commitments not revealed yet
+    pub pending: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestStatus {
+    Pending,
+    Dispatched,
+    // the callback failed, it can be retried
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RequestResponse {
+    pub id: u64,
+    pub requester: Addr,
+    pub request_id: u64,
+    pub resolve_height: u64,
+    pub status: RequestStatus,
+}
+
+/// Callback a requester receives with the randomness derived for its request
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RandomnessCallback {
+    pub request_id: u64,
+    pub resolve_height: u64,
+    pub randomness: Binary,
+    pub callback_msg: Binary,
+}
+
+impl RandomnessCallback {
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = ReceiverExecuteMsg::ReceiveRandomness(self);
+        to_binary(&msg)
+    }
+
+    pub fn into_cosmos_msg(self, contract_addr: Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }))
+    }
+}
+
+// the message a requester has to handle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiverExecuteMsg {
+    ReceiveRandomness(RandomnessCallback),
 }
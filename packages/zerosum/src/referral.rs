@@ -1,8 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cw20::{Cw20ReceiveMsg};
-use cosmwasm_std::{Uint128, Addr, Decimal};
+use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg};
+use cosmwasm_std::{to_binary, Uint128, Addr, Coin, CosmosMsg, Decimal, StdResult, WasmMsg};
+
+use crate::asset::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -13,6 +15,11 @@ pub struct InstantiateMsg {
     pub referral_ratio: Option<Vec<Decimal>>,
     pub collector_contract: Option<Addr>,
     pub reward_contract: Option<Addr>,
+    pub rename_code_fee: Option<Uint128>,
+    pub following_cooldown: Option<u64>,
+    pub reward_expiry: Option<u64>,
+    pub deregister_tenure: Option<u64>,
+    pub deregister_refund_ratio: Option<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -28,16 +35,49 @@ pub enum ExecuteMsg {
         referral_ratio: Option<Vec<Decimal>>,
         collector_contract: Option<Addr>,
         reward_contract: Option<Addr>,
+        rename_code_fee: Option<Uint128>,
+        following_cooldown: Option<u64>,
+        reward_expiry: Option<u64>,
+        deregister_tenure: Option<u64>,
+        deregister_refund_ratio: Option<Decimal>,
     },
     AddShare {
         address: Addr,
         amount: Uint128
     },
+    // claims at most `max_rounds` rounds, the rest in later claims
+    // `owner` claimed by its delegate, which always pays the owner
     Claim {
-        start_round: Option<u64>
+        start_round: Option<u64>,
+        max_rounds: Option<u64>,
+        owner: Option<String>,
+        recipient: Option<String>,
+    },
+    ClaimReferral {
+        owner: Option<String>,
+        recipient: Option<String>,
     },
-    ClaimReferral {},
+    // address that can claim for the sender, None removes it
+    SetClaimDelegate { delegate: Option<String> },
+    // sends expired round rewards (at most `limit` rounds) and the expired balances of `referrers` to the collector
+    SweepExpired { referrers: Option<Vec<Addr>>, limit: Option<u64> },
+    // house edge of a wager of the player in native funds, only from the house
+    AddEdge { address: Addr },
     AddFollowing { address: Addr },
+    AddFollowingByCode { code: String },
+    // allowed `following_cooldown` rounds after the last (change of) following
+    ChangeFollowing { address: Addr },
+    // tiers in ascending `min_volume`, volume counted over the last `window` rounds
+    UpdateTiers { window: u64, tiers: Vec<Tier> },
+    // part of the reward from a direct follower that goes back to the follower
+    SetKickback { ratio: Decimal },
+    // rules a referrer must meet to earn, the rewards it can not earn go to the collector
+    UpdateQualification { qualification: Qualification },
+    // refunds `deregister_refund_ratio` of the fee after `deregister_tenure` rounds and burns the rest,
+    // the followers follow the referrer above, the ones beyond the first 20 are moved by MigrateFollowers
+    Deregister {},
+    // anyone, moves up to `limit` followers left by a deregistered referrer to the referrer above
+    MigrateFollowers { referrer: Addr, limit: Option<u64> },
     Collect {},
 }
 
@@ -48,8 +88,33 @@ pub enum Cw20HookMsg {
         addr: Option<Addr>,
         name: Option<String>,
         description: Option<String>,
+        // shareable code of the referrer, case-insensitive
+        code: Option<String>,
     },
     AddRound { round: u64 },
+    // the fee goes to the collector
+    RenameCode { code: String },
+    // house edge of a wager of the player in the token, only from the house
+    AddEdge { address: Addr },
+}
+
+pub fn edge_msg(contract: Addr, player: Addr, asset: Asset) -> StdResult<CosmosMsg> {
+    match asset.info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract.to_string(),
+            msg: to_binary(&ExecuteMsg::AddEdge { address: player })?,
+            funds: vec![Coin { denom, amount: asset.amount }],
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: contract.to_string(),
+                amount: asset.amount,
+                msg: to_binary(&Cw20HookMsg::AddEdge { address: player })?,
+            })?,
+            funds: vec![],
+        })),
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -57,7 +122,8 @@ pub enum Cw20HookMsg {
 pub enum QueryMsg {
     GetState {},
     GetReferral { addr: Addr },
-    GetReferrals { start_after: Option<u64>, limit: Option<u64> },
+    // deprecated, the referrals carry no address to page with, use ListReferrals
+    GetReferrals { start_after: Option<Addr>, limit: Option<u64> },
     GetRound { round: u64 },
     GetRounds { start_after: Option<u64>, limit: Option<u64> },
     GetFollowing { addr: Addr },
@@ -66,7 +132,151 @@ pub enum QueryMsg {
     GetRewardShares { round: Option<u64> },
     GetReferralReward { addr: Addr },
     GetReward { addr: Addr },
-    GetLastClaimRound { addr: Addr }
+    GetLastClaimRound { addr: Addr },
+    // pending rounds split into claims of `max_rounds`
+    EstimateClaim { addr: Addr, start_round: Option<u64>, max_rounds: Option<u64> },
+    ReferralByCode { code: String },
+    GetTiers {},
+    GetReferrerTier { addr: Addr },
+    GetKickback { addr: Addr },
+    // kickbacks the follower got back from the referrer
+    GetKickbackHistory { follower: Addr, referrer: Addr },
+    GetQualification {},
+    GetQualified { addr: Addr },
+    GetClaimDelegate { addr: Addr },
+    ListReferrals { start_after: Option<Addr>, limit: Option<u64> },
+    // ordered by follower address
+    ListFollowers { addr: Addr, start_after: Option<Addr>, limit: Option<u64> },
+    // referrers above the address, nearest first
+    GetUpline { addr: Addr },
+    GetDownline { addr: Addr },
+    GetFollowingHistory { addr: Addr },
+    // unclaimed rewards of the address and the round they expire
+    GetExpiring { addr: Addr, start_round: Option<u64> },
+    // all-time ranking without a round
+    GetLeaderboard { board: LeaderboardKind, round: Option<u64>, limit: Option<u64> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    // shares of the followers
+    Volume,
+    // referral rewards earned
+    Reward,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimEstimateResponse {
+    pub rounds_remaining: u64,
+    pub windows: Vec<ClaimWindow>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimWindow {
+    pub start_round: u64,
+    pub end_round: u64,
+    // reward of the rounds before the referral split
+    pub reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpiringResponse {
+    pub claimable_reward: Uint128,
+    // None if the balance does not expire
+    pub claimable_expire_round: Option<u64>,
+    pub rounds: Vec<ExpiringRound>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpiringRound {
+    pub round: u64,
+    pub reward: Uint128,
+    // first round the reward can not be claimed
+    pub expire_round: u64,
+}
+
+// a referrer followed before, it gets the rewards of the rounds before `to_round`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FollowingHistory {
+    pub referrer: Addr,
+    pub to_round: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralInfo {
+    pub address: Addr,
+    pub name: String,
+    pub description: String,
+    pub code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FollowerInfo {
+    pub index: u64,
+    pub address: Addr,
+    // rewards the referrer got from the follower
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DownlineResponse {
+    // followers at each level, direct followers first
+    pub levels: Vec<u64>,
+    pub total: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Tier {
+    pub name: String,
+    // volume of the followers a referrer needs for the tier
+    pub min_volume: Uint128,
+    // ratios in the order of `referral_ratio` after the claimer return, falls back to it past its length
+    pub level_ratios: Vec<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct Qualification {
+    // ZEROSUM a referrer holds to earn from its followers
+    pub min_balance: Uint128,
+    // followers with `min_follower_volume` in the last round a referrer needs to earn beyond its direct followers
+    pub min_active_followers: u64,
+    pub min_follower_volume: Uint128,
+    // reward a referrer earns from a single follower per round, 0 without a cap
+    pub max_follower_reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QualifiedResponse {
+    pub balance: Uint128,
+    pub active_followers: u64,
+    // earns from its direct followers
+    pub direct: bool,
+    // earns from the followers of its followers
+    pub upline: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TiersResponse {
+    pub window: u64,
+    pub tiers: Vec<Tier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferrerTierResponse {
+    pub volume: Uint128,
+    pub tier: Option<Tier>,
+    // volume still missing to the next tier
+    pub next_tier: Option<Tier>,
+    pub remaining_volume: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralCodeResponse {
+    pub address: Addr,
+    pub code: String,
+    pub name: String,
+    pub description: String,
 }
 
 // We define a custom struct for each query response
@@ -0,0 +1,791 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Uint128, Addr,
+        CosmosMsg, WasmMsg, Order, Coin, Storage, SubMsg, Reply};
+use cw2::set_contract_version;
+use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg};
+
+use cw_storage_plus::{Bound};
+
+use zerosum::asset::{Asset, AssetInfo, token_asset, token_asset_info};
+use zerosum::house::{ExecuteMsg as HouseExecuteMsg, Cw20HookMsg as HouseCw20HookMsg};
+use zerosum::querier::{query_random};
+use zerosum::prize_pool::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, DepositorResponse, PeriodResponse, WinnerInfo};
+
+use crate::error::ContractError;
+use crate::state::{State, STATE, Balance, TOTAL, DEPOSITORS, WEIGHTS, TOTAL_WEIGHTS, PendingDraw, PENDING_DRAW, DrawInfo, DRAWS,
+    CLAIM_BALANCE};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:prize_pool";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// depositors counted by a single CompleteDraw
+const DRAW_BATCH: usize = 100;
+// reply id of the house claim
+const CLAIM_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = State {
+        gov_contract: info.sender.clone(),
+        asset_info: msg.asset_info,
+        zerosum_token: msg.zerosum_token.unwrap_or(Addr::unchecked("")),
+        house_contract: msg.house_contract.unwrap_or(Addr::unchecked("")),
+        random_contract: msg.random_contract.unwrap_or(Addr::unchecked("")),
+        start_height: env.block.height,
+        period_blocks: msg.period_blocks.unwrap_or(100_000u64).max(1),
+        draw_delay: msg.draw_delay.unwrap_or(10u64).max(1),
+        winner_count: msg.winner_count.unwrap_or(1u32).max(1),
+        next_draw_period: 0,
+        prize: Uint128::zero(),
+    };
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    STATE.save(deps.storage, &state)?;
+    TOTAL.save(deps.storage, &new_balance(&state, env.block.height))?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("gov_contract", info.sender))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateState {
+            gov_contract,
+            zerosum_token,
+            house_contract,
+            random_contract,
+            draw_delay,
+            winner_count,
+        } => execute_update_state(deps, info, gov_contract, zerosum_token, house_contract, random_contract, draw_delay, winner_count),
+        ExecuteMsg::Deposit {} => {
+            let state: State = STATE.load(deps.storage)?;
+            let denom = match state.asset_info {
+                AssetInfo::NativeToken { denom } => denom,
+                AssetInfo::Token { .. } => return Err(ContractError::Unauthorized {}),
+            };
+            let amount = info.funds.iter().find(|coin| coin.denom == denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            execute_deposit(deps, env, info.sender, amount)
+        },
+        ExecuteMsg::Withdraw { amount } => execute_withdraw(deps, env, info, amount),
+        ExecuteMsg::StartDraw {} => execute_start_draw(deps, env),
+        ExecuteMsg::CompleteDraw {} => execute_complete_draw(deps, env),
+    }
+}
+
+fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::Deposit {}) => {
+            let state: State = STATE.load(deps.storage)?;
+            if !state.asset_info.equal(&token_asset_info(info.sender)) {
+                return Err(ContractError::Unauthorized {});
+            }
+            execute_deposit(deps, env, Addr::unchecked(cw20_msg.sender), cw20_msg.amount)
+        },
+        Err(err) => Err(ContractError::Std(err)),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_state(
+    deps: DepsMut,
+    info: MessageInfo,
+    gov_contract: Option<Addr>,
+    zerosum_token: Option<Addr>,
+    house_contract: Option<Addr>,
+    random_contract: Option<Addr>,
+    draw_delay: Option<u64>,
+    winner_count: Option<u32>,
+) -> Result<Response, ContractError> {
+    STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        if state.gov_contract != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if let Some(gov_contract) = gov_contract {
+            state.gov_contract = gov_contract;
+        }
+        if let Some(zerosum_token) = zerosum_token {
+            state.zerosum_token = zerosum_token;
+        }
+        if let Some(house_contract) = house_contract {
+            state.house_contract = house_contract;
+        }
+        if let Some(random_contract) = random_contract {
+            state.random_contract = random_contract;
+        }
+        if let Some(draw_delay) = draw_delay {
+            state.draw_delay = draw_delay.max(1);
+        }
+        if let Some(winner_count) = winner_count {
+            state.winner_count = winner_count.max(1);
+        }
+        Ok(state)
+    })?;
+    Ok(Response::new().add_attribute("method", "update_state"))
+}
+
+fn execute_deposit(deps: DepsMut, env: Env, sender: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::Insufficient {});
+    }
+    let state: State = STATE.load(deps.storage)?;
+
+    let mut balance = DEPOSITORS.may_load(deps.storage, sender.clone())?
+        .unwrap_or_else(|| new_balance(&state, env.block.height));
+    save_snapshots(deps.storage, Some(&sender), sync_balance(&state, &mut balance, env.block.height)?)?;
+    balance.amount += amount;
+    DEPOSITORS.save(deps.storage, sender.clone(), &balance)?;
+
+    let mut total: Balance = TOTAL.load(deps.storage)?;
+    save_snapshots(deps.storage, None, sync_balance(&state, &mut total, env.block.height)?)?;
+    total.amount += amount;
+    TOTAL.save(deps.storage, &total)?;
+
+    Ok(Response::new()
+        .add_message(house_deposit_msg(&state, amount)?)
+        .add_attribute("method", "deposit")
+        .add_attribute("depositor", sender)
+        .add_attribute("amount", amount))
+}
+
+fn execute_withdraw(mut deps: DepsMut, env: Env, info: MessageInfo, amount: Option<Uint128>) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    let mut balance: Balance = DEPOSITORS.may_load(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::NotExist {})?;
+    let amount = amount.unwrap_or(balance.amount);
+    if amount.is_zero() || amount > balance.amount {
+        return Err(ContractError::Insufficient {});
+    }
+
+    save_snapshots(deps.storage, Some(&info.sender), sync_balance(&state, &mut balance, env.block.height)?)?;
+    balance.amount -= amount;
+    if balance.amount.is_zero() && balance.weight.is_zero() && !has_undrawn_weight(deps.storage, &state, &info.sender, balance.period)? {
+        DEPOSITORS.remove(deps.storage, info.sender.clone());
+    } else {
+        DEPOSITORS.save(deps.storage, info.sender.clone(), &balance)?;
+    }
+
+    let mut total: Balance = TOTAL.load(deps.storage)?;
+    save_snapshots(deps.storage, None, sync_balance(&state, &mut total, env.block.height)?)?;
+    total.amount -= amount;
+    TOTAL.save(deps.storage, &total)?;
+
+    // the house only withdraws a whole deposit, so the rest goes straight back in.
+    // rewards are claimed first so the withdraw pays none and the prize counts them.
+    let mut msgs: Vec<SubMsg> = vec![
+        house_claim_msg(deps.branch(), &env, &state)?,
+        SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.house_contract.to_string(),
+            funds: vec![],
+            msg: to_binary(&HouseExecuteMsg::Withdraw { asset_info: state.asset_info.clone() })?,
+        })),
+    ];
+    if !total.amount.is_zero() {
+        msgs.push(SubMsg::new(house_deposit_msg(&state, total.amount)?));
+    }
+    msgs.push(SubMsg::new(Asset {
+        info: state.asset_info,
+        amount,
+    }.into_msg(&deps.querier, info.sender.clone())?));
+
+    Ok(Response::new().add_submessages(msgs)
+        .add_attribute("method", "withdraw")
+        .add_attribute("depositor", info.sender)
+        .add_attribute("amount", amount))
+}
+
+fn execute_start_draw(mut deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    // periods are drawn one by one in order, even when the draws fall behind
+    let period = state.next_draw_period;
+    if period >= get_period(&state, env.block.height) {
+        return Err(ContractError::NotFinished {});
+    }
+    if PENDING_DRAW.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::AlreadyExist {});
+    }
+
+    let mut total: Balance = TOTAL.load(deps.storage)?;
+    save_snapshots(deps.storage, None, sync_balance(&state, &mut total, env.block.height)?)?;
+    TOTAL.save(deps.storage, &total)?;
+    let total_weight = weight_of(&state, &total, TOTAL_WEIGHTS.may_load(deps.storage, period)?, period)?;
+
+    let draw_height = env.block.height + state.draw_delay;
+    PENDING_DRAW.save(deps.storage, &PendingDraw {
+        period,
+        draw_height,
+        start_height: Some(env.block.height),
+        total_weight,
+        share: None,
+        targets: vec![],
+        cursor: None,
+        cumulative: Uint128::zero(),
+        winners: vec![],
+    })?;
+
+    let mut msgs: Vec<SubMsg> = vec![];
+    if !total.amount.is_zero() {
+        msgs.push(house_claim_msg(deps.branch(), &env, &state)?);
+    }
+
+    Ok(Response::new().add_submessages(msgs)
+        .add_attribute("method", "start_draw")
+        .add_attribute("period", period.to_string())
+        .add_attribute("draw_height", draw_height.to_string())
+        .add_attribute("total_weight", total_weight))
+}
+
+fn execute_complete_draw(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    let mut pending: PendingDraw = PENDING_DRAW.may_load(deps.storage)?.ok_or(ContractError::NotExist {})?;
+    if env.block.height <= pending.draw_height {
+        return Err(ContractError::NotFed {});
+    }
+
+    let share = match pending.share {
+        Some(share) => share,
+        None => {
+            let share = state.prize / Uint128::from(state.winner_count);
+            // nothing to win or nobody to win it, the prize rolls over to the next draw
+            if !pending.total_weight.is_zero() && !share.is_zero() {
+                for idx in 0..state.winner_count {
+                    let mut entropy = pending.period.to_be_bytes().to_vec();
+                    entropy.extend(idx.to_be_bytes());
+                    let random = query_random(&deps.querier, state.random_contract.clone(), pending.draw_height, Some(entropy), u32::MAX, pending.start_height)?
+                        .ok_or(ContractError::NotFed {})?;
+                    pending.targets.push(pending.total_weight.multiply_ratio(random as u128, u32::MAX as u128 + 1));
+                }
+                pending.targets.sort();
+            }
+            pending.share = Some(share);
+            share
+        },
+    };
+
+    // depositors are walked in batches so the draw never outgrows the gas limit
+    let start = pending.cursor.as_ref().map(|addr| Bound::ExclusiveRaw(addr.as_bytes().to_vec()));
+    let depositors: Vec<(Addr, Balance)> = DEPOSITORS.range(deps.storage, start, None, Order::Ascending)
+        .take(DRAW_BATCH)
+        .collect::<StdResult<_>>()?;
+    for (address, balance) in depositors.iter() {
+        let snapshot = WEIGHTS.may_load(deps.storage, (pending.period, address.clone()))?;
+        let weight = weight_of(&state, balance, snapshot, pending.period)?;
+        pending.cumulative += weight;
+        while let Some(target) = pending.targets.first() {
+            if *target >= pending.cumulative {
+                break;
+            }
+            pending.targets.remove(0);
+            match pending.winners.iter_mut().find(|winner| winner.address == *address) {
+                Some(winner) => winner.amount += share,
+                None => pending.winners.push(WinnerInfo { address: address.clone(), amount: share }),
+            }
+        }
+        WEIGHTS.remove(deps.storage, (pending.period, address.clone()));
+        // left without any weight in the later periods
+        if balance.amount.is_zero() && (balance.period <= pending.period || balance.weight.is_zero())
+            && !has_undrawn_weight(deps.storage, &state, address, balance.period)? {
+            DEPOSITORS.remove(deps.storage, address.clone());
+        }
+    }
+    if let Some((address, _)) = depositors.last() {
+        pending.cursor = Some(address.clone());
+    }
+    if depositors.len() == DRAW_BATCH {
+        PENDING_DRAW.save(deps.storage, &pending)?;
+        return Ok(Response::new()
+            .add_attribute("method", "complete_draw")
+            .add_attribute("period", pending.period.to_string())
+            .add_attribute("cursor", pending.cursor.map(|addr| addr.to_string()).unwrap_or_default()));
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for winner in pending.winners.iter() {
+        msgs.push(token_asset(state.zerosum_token.clone(), winner.amount).into_msg(&deps.querier, winner.address.clone())?);
+    }
+
+    let awarded = pending.winners.iter().fold(Uint128::zero(), |acc, winner| acc + winner.amount);
+    PENDING_DRAW.remove(deps.storage);
+    TOTAL_WEIGHTS.remove(deps.storage, pending.period);
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        state.next_draw_period = pending.period + 1;
+        state.prize = state.prize.checked_sub(awarded)?;
+        Ok(state)
+    })?;
+    DRAWS.save(deps.storage, pending.period, &DrawInfo {
+        period: pending.period,
+        draw_height: pending.draw_height,
+        total_weight: pending.cumulative,
+        prize: awarded,
+        winners: pending.winners,
+    })?;
+
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "complete_draw")
+        .add_attribute("period", pending.period.to_string())
+        .add_attribute("prize", awarded))
+}
+
+// the house fails the claim before its rounds start, which must not fail the caller
+fn house_claim_msg(deps: DepsMut, env: &Env, state: &State) -> StdResult<SubMsg> {
+    let balance = token_asset_info(state.zerosum_token.clone()).query_balance(&deps.querier, env.contract.address.clone())?;
+    CLAIM_BALANCE.save(deps.storage, &balance)?;
+    Ok(SubMsg::reply_always(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: state.house_contract.to_string(),
+        funds: vec![],
+        msg: to_binary(&HouseExecuteMsg::Claim { asset_info: state.asset_info.clone(), owner: None, recipient: None })?,
+    }), CLAIM_REPLY_ID))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    if msg.id != CLAIM_REPLY_ID {
+        return Err(ContractError::NotExist {});
+    }
+    let before = CLAIM_BALANCE.load(deps.storage)?;
+    CLAIM_BALANCE.remove(deps.storage);
+    let mut claimed = Uint128::zero();
+    if msg.result.is_ok() {
+        let state: State = STATE.load(deps.storage)?;
+        let balance = token_asset_info(state.zerosum_token.clone()).query_balance(&deps.querier, env.contract.address)?;
+        claimed = balance.saturating_sub(before);
+        STATE.save(deps.storage, &State {
+            prize: state.prize + claimed,
+            ..state
+        })?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "reply")
+        .add_attribute("claimed", claimed))
+}
+
+fn house_deposit_msg(state: &State, amount: Uint128) -> StdResult<CosmosMsg> {
+    match &state.asset_info {
+        AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.house_contract.to_string(),
+            funds: vec![Coin { denom: denom.clone(), amount }],
+            msg: to_binary(&HouseExecuteMsg::Deposit {})?,
+        })),
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: state.house_contract.to_string(),
+                amount,
+                msg: to_binary(&HouseCw20HookMsg::Deposit {})?,
+            })?,
+        })),
+    }
+}
+
+fn get_period(state: &State, height: u64) -> u64 {
+    height.saturating_sub(state.start_height) / state.period_blocks
+}
+
+fn period_start(state: &State, period: u64) -> StdResult<u64> {
+    period.checked_mul(state.period_blocks)
+        .and_then(|blocks| blocks.checked_add(state.start_height))
+        .ok_or_else(|| StdError::generic_err("period overflow"))
+}
+
+fn new_balance(state: &State, height: u64) -> Balance {
+    Balance {
+        amount: Uint128::zero(),
+        weight: Uint128::zero(),
+        period: get_period(state, height),
+        last_height: height,
+    }
+}
+
+/// Accrues the balance up to `height`. When the period changed since the last touch,
+/// returns the weights of every period that got closed by it and is not drawn yet
+/// so the later draws can still read them.
+fn sync_balance(state: &State, balance: &mut Balance, height: u64) -> StdResult<Vec<(u64, Uint128)>> {
+    let current_period = get_period(state, height);
+    let mut snapshots = vec![];
+    if balance.period == current_period {
+        balance.weight = balance.weight.checked_add(balance.amount.checked_mul(Uint128::from(height - balance.last_height))?)?;
+    } else {
+        let end = period_start(state, balance.period + 1)?;
+        if balance.period >= state.next_draw_period {
+            snapshots.push((balance.period, balance.weight.checked_add(balance.amount.checked_mul(Uint128::from(end - balance.last_height))?)?));
+        }
+        for period in (balance.period + 1).max(state.next_draw_period)..current_period {
+            snapshots.push((period, balance.amount.checked_mul(Uint128::from(state.period_blocks))?));
+        }
+        balance.period = current_period;
+        balance.weight = balance.amount.checked_mul(Uint128::from(height - period_start(state, current_period)?))?;
+    }
+    balance.last_height = height;
+    Ok(snapshots)
+}
+
+fn save_snapshots(storage: &mut dyn Storage, address: Option<&Addr>, snapshots: Vec<(u64, Uint128)>) -> StdResult<()> {
+    let pending = PENDING_DRAW.may_load(storage)?;
+    for (period, weight) in snapshots.into_iter() {
+        // a missing snapshot reads as zero
+        if weight.is_zero() {
+            continue;
+        }
+        // the running draw already counted the depositors up to its cursor
+        if let Some(pending) = &pending {
+            if pending.period == period && address.is_some() && address <= pending.cursor.as_ref() {
+                continue;
+            }
+        }
+        match address {
+            Some(address) => WEIGHTS.save(storage, (period, address.clone()), &weight)?,
+            None => TOTAL_WEIGHTS.save(storage, period, &weight)?,
+        }
+    }
+    Ok(())
+}
+
+/// Whether a snapshot is still waiting for one of the draws before `current_period`
+fn has_undrawn_weight(storage: &dyn Storage, state: &State, address: &Addr, current_period: u64) -> StdResult<bool> {
+    for period in state.next_draw_period..current_period {
+        if WEIGHTS.has(storage, (period, address.clone())) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Time-weighted balance of a finished `period`
+fn weight_of(state: &State, balance: &Balance, snapshot: Option<Uint128>, period: u64) -> StdResult<Uint128> {
+    if balance.period == period {
+        let end = period_start(state, period + 1)?;
+        Ok(balance.weight.checked_add(balance.amount.checked_mul(Uint128::from(end - balance.last_height))?)?)
+    } else if balance.period < period {
+        Ok(balance.amount.checked_mul(Uint128::from(state.period_blocks))?)
+    } else {
+        Ok(snapshot.unwrap_or_default())
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::CurrentPeriod {} => to_binary(&query_current_period(deps, env)?),
+        QueryMsg::Depositor { address } => to_binary(&query_depositor(deps, env, address)?),
+        QueryMsg::Depositors { start_after, limit } => to_binary(&query_depositors(deps, env, start_after, limit)?),
+        QueryMsg::PendingDraw {} => to_binary(&query_pending_draw(deps)?),
+        QueryMsg::Draw { period } => to_binary(&query_draw(deps, period)?),
+        QueryMsg::Draws { start_after, limit } => to_binary(&query_draws(deps, start_after, limit)?),
+    }
+}
+
+fn query_state(deps: Deps) -> StdResult<State> {
+    let state = STATE.load(deps.storage)?;
+    Ok(state)
+}
+
+fn query_current_period(deps: Deps, env: Env) -> StdResult<PeriodResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let mut total: Balance = TOTAL.load(deps.storage)?;
+    sync_balance(&state, &mut total, env.block.height)?;
+    Ok(PeriodResponse {
+        period: total.period,
+        start_height: period_start(&state, total.period)?,
+        end_height: period_start(&state, total.period + 1)?,
+        total_amount: total.amount,
+        total_weight: total.weight,
+    })
+}
+
+fn query_depositor(deps: Deps, env: Env, address: Addr) -> StdResult<DepositorResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let mut balance: Balance = DEPOSITORS.load(deps.storage, address.clone())?;
+    sync_balance(&state, &mut balance, env.block.height)?;
+    Ok(DepositorResponse {
+        address,
+        amount: balance.amount,
+        weight: balance.weight,
+    })
+}
+
+fn query_depositors(deps: Deps, env: Env, start_after: Option<Addr>, limit: Option<u64>) -> StdResult<Vec<DepositorResponse>> {
+    let state: State = STATE.load(deps.storage)?;
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.as_bytes().to_vec()));
+    DEPOSITORS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (address, mut balance) = item?;
+        sync_balance(&state, &mut balance, env.block.height)?;
+        Ok(DepositorResponse {
+            address,
+            amount: balance.amount,
+            weight: balance.weight,
+        })
+    }).collect()
+}
+
+fn query_pending_draw(deps: Deps) -> StdResult<Option<PendingDraw>> {
+    PENDING_DRAW.may_load(deps.storage)
+}
+
+fn query_draw(deps: Deps, period: u64) -> StdResult<DrawInfo> {
+    DRAWS.load(deps.storage, period)
+}
+
+fn query_draws(deps: Deps, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<DrawInfo>> {
+    let start = start_after.map(|period| Bound::ExclusiveRaw(period.to_be_bytes().to_vec()));
+    DRAWS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (_, draw) = item?;
+        Ok(draw)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, from_binary, ContractResult, OwnedDeps, SubMsgResponse, SubMsgResult, SystemResult, WasmQuery};
+    use cw20::BalanceResponse;
+
+    fn mock_env_height(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    // the random contract answers `random`, the zerosum token the balance of the pool
+    fn mock_wasm(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, random: u32, balance: u64) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "random" => {
+                SystemResult::Ok(ContractResult::Ok(to_binary(&Some(random)).unwrap()))
+            },
+            _ => SystemResult::Ok(ContractResult::Ok(to_binary(&BalanceResponse { balance: Uint128::from(balance) }).unwrap())),
+        });
+    }
+
+    fn mock_claim_reply(deps: DepsMut, result: SubMsgResult) {
+        reply(deps, mock_env(), Reply { id: CLAIM_REPLY_ID, result }).unwrap();
+    }
+
+    fn claim_succeeded() -> SubMsgResult {
+        SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None })
+    }
+
+    fn mock_instantiate(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            asset_info: AssetInfo::NativeToken { denom: "uusd".to_string() },
+            zerosum_token: Some(Addr::unchecked("zerosum_token")),
+            house_contract: Some(Addr::unchecked("house")),
+            random_contract: Some(Addr::unchecked("random")),
+            period_blocks: Some(100),
+            draw_delay: Some(10),
+            winner_count: Some(1),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps, mock_env_height(100), info, msg).unwrap();
+    }
+
+    #[test]
+    fn deposit_and_withdraw() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_wasm(&mut deps, 0, 0);
+
+        let info = mock_info("user1", &coins(100, "ukrw"));
+        let res = execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Deposit {});
+        match res {
+            Err(ContractError::Insufficient {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let info = mock_info("user1", &coins(100, "uusd"));
+        let res = execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Deposit {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env_height(150), QueryMsg::Depositor { address: Addr::unchecked("user1") }).unwrap();
+        let depositor: DepositorResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(100u64), depositor.amount);
+        assert_eq!(Uint128::from(5000u64), depositor.weight);
+
+        let info = mock_info("user1", &[]);
+        let res = execute(deps.as_mut(), mock_env_height(150), info.clone(), ExecuteMsg::Withdraw { amount: Some(Uint128::from(101u64)) });
+        match res {
+            Err(ContractError::Insufficient {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        // house claim, house withdraw, deposit the rest back, send to the user
+        let res = execute(deps.as_mut(), mock_env_height(150), info, ExecuteMsg::Withdraw { amount: Some(Uint128::from(40u64)) }).unwrap();
+        assert_eq!(4, res.messages.len());
+        assert_eq!(CLAIM_REPLY_ID, res.messages[0].id);
+
+        // rewards paid by the claim count as prize
+        mock_wasm(&mut deps, 0, 30);
+        mock_claim_reply(deps.as_mut(), claim_succeeded());
+        assert_eq!(Uint128::from(30u64), STATE.load(&deps.storage).unwrap().prize);
+
+        let res = query(deps.as_ref(), mock_env_height(160), QueryMsg::CurrentPeriod {}).unwrap();
+        let period: PeriodResponse = from_binary(&res).unwrap();
+        assert_eq!(0, period.period);
+        assert_eq!(Uint128::from(60u64), period.total_amount);
+        assert_eq!(Uint128::from(5600u64), period.total_weight);
+    }
+
+    #[test]
+    fn draw() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_wasm(&mut deps, u32::MAX / 2, 500);
+
+        let info = mock_info("user1", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = mock_info("user2", &coins(300, "uusd"));
+        execute(deps.as_mut(), mock_env_height(150), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let res = execute(deps.as_mut(), mock_env_height(190), mock_info("anyone", &[]), ExecuteMsg::StartDraw {});
+        match res {
+            Err(ContractError::NotFinished {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env_height(210), mock_info("anyone", &[]), ExecuteMsg::StartDraw {}).unwrap();
+        assert_eq!(CLAIM_REPLY_ID, res.messages[0].id);
+        // only the claimed rewards are drawn, not the balance already held
+        mock_wasm(&mut deps, u32::MAX / 2, 1500);
+        mock_claim_reply(deps.as_mut(), claim_succeeded());
+        let res = query(deps.as_ref(), mock_env_height(210), QueryMsg::PendingDraw {}).unwrap();
+        let pending: Option<PendingDraw> = from_binary(&res).unwrap();
+        assert_eq!(Some(PendingDraw {
+            period: 0,
+            draw_height: 220,
+            start_height: Some(210),
+            total_weight: Uint128::from(25000u64),
+            share: None,
+            targets: vec![],
+            cursor: None,
+            cumulative: Uint128::zero(),
+            winners: vec![],
+        }), pending);
+
+        // leaving after the period ended keeps the weight of the drawn period
+        let info = mock_info("user2", &[]);
+        execute(deps.as_mut(), mock_env_height(212), info, ExecuteMsg::Withdraw { amount: None }).unwrap();
+        // a failing claim adds nothing
+        mock_claim_reply(deps.as_mut(), SubMsgResult::Err(String::from("Not Exist")));
+        assert_eq!(Uint128::from(1000u64), STATE.load(&deps.storage).unwrap().prize);
+
+        let res = execute(deps.as_mut(), mock_env_height(215), mock_info("anyone", &[]), ExecuteMsg::CompleteDraw {});
+        match res {
+            Err(ContractError::NotFed {}) => {},
+            _ => panic!("must return error"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env_height(221), mock_info("anyone", &[]), ExecuteMsg::CompleteDraw {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env_height(221), QueryMsg::Draw { period: 0 }).unwrap();
+        let draw: DrawInfo = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(25000u64), draw.total_weight);
+        assert_eq!(vec![WinnerInfo { address: Addr::unchecked("user2"), amount: Uint128::from(1000u64) }], draw.winners);
+        assert_eq!(Uint128::zero(), STATE.load(&deps.storage).unwrap().prize);
+
+        let res = execute(deps.as_mut(), mock_env_height(230), mock_info("anyone", &[]), ExecuteMsg::StartDraw {});
+        match res {
+            Err(ContractError::NotFinished {}) => {},
+            _ => panic!("must return error"),
+        }
+    }
+
+    #[test]
+    fn batched_draw() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+        mock_wasm(&mut deps, u32::MAX, 0);
+
+        for idx in 0..=DRAW_BATCH {
+            let info = mock_info(&format!("user{:03}", idx), &coins(100, "uusd"));
+            execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Deposit {}).unwrap();
+        }
+
+        // leaving without any weight removes the depositor right away
+        let info = mock_info("leaver", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Deposit {}).unwrap();
+        let info = mock_info("leaver", &[]);
+        execute(deps.as_mut(), mock_env_height(100), info, ExecuteMsg::Withdraw { amount: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(100), QueryMsg::Depositor { address: Addr::unchecked("leaver") });
+        assert!(res.is_err());
+
+        // leaving with weight in an undrawn period waits for the draw
+        let info = mock_info("user000", &[]);
+        execute(deps.as_mut(), mock_env_height(200), info, ExecuteMsg::Withdraw { amount: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(200), QueryMsg::Depositor { address: Addr::unchecked("user000") });
+        assert!(res.is_ok());
+
+        execute(deps.as_mut(), mock_env_height(210), mock_info("anyone", &[]), ExecuteMsg::StartDraw {}).unwrap();
+        mock_wasm(&mut deps, u32::MAX, 1000);
+        mock_claim_reply(deps.as_mut(), claim_succeeded());
+
+        let res = execute(deps.as_mut(), mock_env_height(221), mock_info("anyone", &[]), ExecuteMsg::CompleteDraw {}).unwrap();
+        assert_eq!(0, res.messages.len());
+        let res = query(deps.as_ref(), mock_env_height(221), QueryMsg::PendingDraw {}).unwrap();
+        let pending: Option<PendingDraw> = from_binary(&res).unwrap();
+        let pending = pending.unwrap();
+        assert_eq!(Some(Addr::unchecked(format!("user{:03}", DRAW_BATCH - 1))), pending.cursor);
+        assert_eq!(Uint128::from(10000u64 * DRAW_BATCH as u64), pending.cumulative);
+
+        let res = execute(deps.as_mut(), mock_env_height(222), mock_info("anyone", &[]), ExecuteMsg::CompleteDraw {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env_height(222), QueryMsg::Draw { period: 0 }).unwrap();
+        let draw: DrawInfo = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(10000u64 * (DRAW_BATCH as u64 + 1)), draw.total_weight);
+        assert_eq!(vec![WinnerInfo { address: Addr::unchecked(format!("user{:03}", DRAW_BATCH)), amount: Uint128::from(1000u64) }], draw.winners);
+
+        let res = query(deps.as_ref(), mock_env_height(222), QueryMsg::Depositor { address: Addr::unchecked("user000") });
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn late_draws() {
+        let mut deps = mock_dependencies();
+        mock_instantiate(deps.as_mut());
+
+        let info = mock_info("user1", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env_height(150), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // touched again three periods later, every skipped period keeps its weight
+        let info = mock_info("user1", &coins(100, "uusd"));
+        execute(deps.as_mut(), mock_env_height(420), info, ExecuteMsg::Deposit {}).unwrap();
+
+        let expected = [5000u64, 10000u64, 10000u64];
+        for (period, weight) in expected.iter().enumerate() {
+            let height = 430 + period as u64 * 20;
+            mock_wasm(&mut deps, 0, 1000 * period as u64);
+            execute(deps.as_mut(), mock_env_height(height), mock_info("anyone", &[]), ExecuteMsg::StartDraw {}).unwrap();
+            mock_wasm(&mut deps, 0, 1000 * (period as u64 + 1));
+            mock_claim_reply(deps.as_mut(), claim_succeeded());
+            execute(deps.as_mut(), mock_env_height(height + 11), mock_info("anyone", &[]), ExecuteMsg::CompleteDraw {}).unwrap();
+
+            let res = query(deps.as_ref(), mock_env_height(height + 11), QueryMsg::Draw { period: period as u64 }).unwrap();
+            let draw: DrawInfo = from_binary(&res).unwrap();
+            assert_eq!(Uint128::from(*weight), draw.total_weight);
+            assert_eq!(vec![WinnerInfo { address: Addr::unchecked("user1"), amount: Uint128::from(1000u64) }], draw.winners);
+        }
+
+        let res = execute(deps.as_mut(), mock_env_height(490), mock_info("anyone", &[]), ExecuteMsg::StartDraw {});
+        match res {
+            Err(ContractError::NotFinished {}) => {},
+            _ => panic!("must return error"),
+        }
+    }
+}
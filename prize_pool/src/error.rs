@@ -0,0 +1,26 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Insufficient")]
+    Insufficient {},
+
+    #[error("Already Exist")]
+    AlreadyExist {},
+
+    #[error("Not Exist")]
+    NotExist {},
+
+    #[error("Not Finished Period")]
+    NotFinished {},
+
+    #[error("Not Fed Seed")]
+    NotFed {},
+}
@@ -0,0 +1,82 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use zerosum::asset::AssetInfo;
+use zerosum::prize_pool::WinnerInfo;
+
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct State {
+    pub gov_contract: Addr,
+    pub asset_info: AssetInfo,
+    pub zerosum_token: Addr,
+    pub house_contract: Addr,
+    pub random_contract: Addr,
+    pub start_height: u64,
+    pub period_blocks: u64,
+    pub draw_delay: u64,
+    pub winner_count: u32,
+    // the oldest period that is not drawn yet
+    #[serde(default)]
+    pub next_draw_period: u64,
+    // rewards claimed from the house and not awarded yet
+    #[serde(default)]
+    pub prize: Uint128,
+}
+
+// principal and time-weighted balance (amount * blocks) of the period it was last touched in
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Balance {
+    pub amount: Uint128,
+    pub weight: Uint128,
+    pub period: u64,
+    pub last_height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingDraw {
+    pub period: u64,
+    pub draw_height: u64,
+    // height the draw was started at, seed contributions committed after it are left out
+    #[serde(default)]
+    pub start_height: Option<u64>,
+    pub total_weight: Uint128,
+    // prize of each winner, fixed by the first batch of the draw
+    #[serde(default)]
+    pub share: Option<Uint128>,
+    // cumulative weights that are not matched to a depositor yet, ascending
+    #[serde(default)]
+    pub targets: Vec<Uint128>,
+    // last depositor counted and the weight counted up to it
+    #[serde(default)]
+    pub cursor: Option<Addr>,
+    #[serde(default)]
+    pub cumulative: Uint128,
+    #[serde(default)]
+    pub winners: Vec<WinnerInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrawInfo {
+    pub period: u64,
+    pub draw_height: u64,
+    pub total_weight: Uint128,
+    pub prize: Uint128,
+    pub winners: Vec<WinnerInfo>,
+}
+
+pub const STATE: Item<State> = Item::new("state");
+pub const TOTAL: Item<Balance> = Item::new("total");
+pub const DEPOSITORS: Map<Addr, Balance> = Map::new("depositors");
+
+// weight of a period that is already closed for the balance, period, address
+pub const WEIGHTS: Map<(u64, Addr), Uint128> = Map::new("weights");
+pub const TOTAL_WEIGHTS: Map<u64, Uint128> = Map::new("total_weights");
+
+// zerosum balance before a house claim, the claimed reward is counted in `reply`
+pub const CLAIM_BALANCE: Item<Uint128> = Item::new("claim_balance");
+
+pub const PENDING_DRAW: Item<PendingDraw> = Item::new("pending_draw");
+pub const DRAWS: Map<u64, DrawInfo> = Map::new("draws");
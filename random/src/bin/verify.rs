@@ -0,0 +1,90 @@
+//! Recomputes a `RandomBetween` outcome offline from the `VerifyOutcome` query of the random contract.
+//!
+//! Every revealed contribution is checked against its commitment, the seed is rebuilt from the
+//! contributions, the drand beacon and the block hash, and the draw is made again from it.
+//!
+//! cargo run --bin verify -- <VerifyOutcome response as json> <min> <max>
+
+use std::env;
+use std::fs;
+use std::process;
+
+use cosmwasm_std::{from_slice, Binary};
+use random::rand::{sha_256, Prng};
+use zerosum::random::VerifyOutcomeResponse;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 {
+        eprintln!("usage: {} <VerifyOutcome response as json> <min> <max>", args[0]);
+        process::exit(1);
+    }
+
+    let data = match fs::read(&args[1]) {
+        Ok(data) => data,
+        Err(_) => fail("can not read the response"),
+    };
+    let outcome: VerifyOutcomeResponse = match from_slice(&data) {
+        Ok(outcome) => outcome,
+        Err(_) => fail("invalid response"),
+    };
+    let min: u32 = parse(&args[2], "min");
+    let max: u32 = parse(&args[3], "max");
+
+    // the contributions, each matching the hash its feeder committed
+    let mut contributions: Vec<u8> = vec![];
+    for contribution in outcome.contributions.iter() {
+        if sha_256(contribution.seed.as_bytes()) != contribution.hash.as_slice() {
+            fail(&format!("contribution of {} does not match its commitment", contribution.feeder));
+        }
+        contributions.extend(contribution.seed.as_bytes());
+    }
+    let feeder_seed = match outcome.seed_height {
+        Some(_) if outcome.contributions.is_empty() => fail("the seed was fed without commitments"),
+        Some(_) => Some(Binary::from(sha_256(&contributions)).to_base64()),
+        None => None,
+    };
+    let drand_seed = outcome.drand_randomness.as_ref().map(|randomness| randomness.to_base64());
+    let seed = match (feeder_seed, drand_seed) {
+        (Some(feeder_seed), Some(drand_seed)) => mix(&feeder_seed, &drand_seed),
+        (Some(feeder_seed), None) => feeder_seed,
+        (None, Some(drand_seed)) => drand_seed,
+        (None, None) => fail("no contributions nor beacon"),
+    };
+    let seed = match outcome.block_hash.as_ref() {
+        Some(block_hash) => mix(&seed, block_hash),
+        None => seed,
+    };
+    if seed != outcome.seed {
+        fail(&format!("seed {} does not match {}", seed, outcome.seed));
+    }
+
+    let mut rng = Prng::new(seed.as_bytes(), outcome.entropy.as_slice());
+    let chacha_seed = Binary::from(rng.chacha_seed());
+    let value = rng.random_between(min, max);
+    println!("seed: {}", seed);
+    println!("chacha_seed: {}", chacha_seed);
+    println!("value: {}", value);
+    if chacha_seed != outcome.chacha_seed || value != outcome.value {
+        fail("the outcome does not match");
+    }
+}
+
+// sha256 of the two seeds concatenated, as the contract mixes them
+fn mix(seed: &str, other: &str) -> String {
+    let mut data = seed.as_bytes().to_vec();
+    data.extend(other.as_bytes());
+    Binary::from(sha_256(&data)).to_base64()
+}
+
+fn parse<T: std::str::FromStr>(value: &str, name: &str) -> T {
+    match value.parse() {
+        Ok(value) => value,
+        Err(_) => fail(&format!("invalid {}", name)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
@@ -1,18 +1,40 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Addr, Order, Decimal};
-use cw_storage_plus::{Bound};
+use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, StdError, Addr, Order, Reply, SubMsg,
+    CosmosMsg, Uint128, Decimal, Storage};
+use cw_storage_plus::{Bound, PrimaryKey};
 use cw2::set_contract_version;
+use cw20::{Cw20ReceiveMsg};
+
+use zerosum::asset::{token_asset};
+use zerosum::querier::{query_block_hash};
+use zerosum::reward::{reward_msg};
 
 use crate::error::ContractError;
-use zerosum::random::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{State, STATE, FEEDERS, SEEDS};
-use crate::rand::{Prng};
+use zerosum::random::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, CommitmentResponse, RandomMode, RandomnessCallback, RequestResponse,
+    RequestStatus, FeederResponse, VerifyOutcomeResponse, SeedResponse, ContributionResponse};
+use crate::state::{State, STATE, FEEDERS, SEEDS, Commitment, COMMITMENTS, SeedRound, SEED_ROUNDS, DrandConfig, DRAND, BEACONS, CHECKPOINTS,
+    RandomnessRequest, REQUESTS, REQUEST_QUEUE, FAILED_REQUESTS, DISPATCH_CURSOR, PRUNED_BEFORE, next_request_index, StakingConfig, STAKING, FEEDER_INFOS, SLASHED,
+    WITHHELD};
+use crate::rand::{Prng, derive_entropy, derive_prng, sha_256};
+use crate::drand;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:random";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// callbacks sent and queued requests looked at per dispatch
+const MAX_DISPATCH: usize = 20;
+const MAX_SCAN: usize = 100;
+// draws a single query returns at most
+const MAX_DRAWS: u32 = 1000;
+// seeds pruned at most per call
+const MAX_PRUNE: usize = 100;
+// blocks a seed is kept for at least, longer than the timeout of the bets resolved from it
+const MIN_SEED_AGE: u64 = 100_800;
+// blocks after the height the reveals are waited for without a staking config
+const DEFAULT_REVEAL_WINDOW: u64 = 10;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -22,6 +44,9 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     let state = State {
         gov_contract: info.sender.clone(),
+        quorum: 1,
+        mode: RandomMode::Feeder,
+        block_entropy: false,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
@@ -39,22 +64,112 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateState { gov_contract } => execute_update_state(deps, info, gov_contract),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateState { gov_contract, quorum, mode, block_entropy } => {
+            execute_update_state(deps, info, gov_contract, quorum, mode, block_entropy)
+        },
         ExecuteMsg::AddFeeder { address } => execute_add_feeder(deps, info, address),
         ExecuteMsg::RemoveFeeder { address } => execute_remove_feeder(deps, info, address),
-        ExecuteMsg::Feed { height, seed } => execute_feed(deps, env, info, height, seed)
+        ExecuteMsg::Feed { height, seed } => execute_feed(deps, env, info, height, seed),
+        ExecuteMsg::CommitSeed { height, hash } => execute_commit_seed(deps, env, info, height, hash),
+        ExecuteMsg::RevealSeed { height, seed } => execute_reveal_seed(deps, env, info, height, seed),
+        ExecuteMsg::FinalizeSeed { height } => execute_finalize_seed(deps, env, height),
+        ExecuteMsg::UpdateDrand { pubkey, genesis_time, period } => execute_update_drand(deps, env, info, pubkey, genesis_time, period),
+        ExecuteMsg::AddBeacon { round, previous_signature, signature } => execute_add_beacon(deps, env, round, previous_signature, signature),
+        ExecuteMsg::RequestRandomness { request_id, resolve_height, entropy, callback_msg } =>
+            execute_request_randomness(deps, env, info, request_id, resolve_height, entropy, callback_msg),
+        ExecuteMsg::DispatchRequests {} => execute_dispatch_requests(deps, env),
+        ExecuteMsg::RetryRequest { id } => execute_retry_request(deps, env, id),
+        ExecuteMsg::UpdateStaking {
+            zerosum_token,
+            reward_contract,
+            collector_contract,
+            min_bond,
+            feed_reward,
+            reveal_window,
+            slash_rate,
+            feed_interval,
+        } => {
+            let mut staking = STAKING.may_load(deps.storage)?.unwrap_or(StakingConfig {
+                zerosum_token: Addr::unchecked(""),
+                reward_contract: Addr::unchecked(""),
+                collector_contract: Addr::unchecked(""),
+                min_bond: Uint128::zero(),
+                feed_reward: Uint128::zero(),
+                reveal_window: 0,
+                slash_rate: Decimal::zero(),
+                feed_interval: 0,
+            });
+            if let Some(zerosum_token) = zerosum_token {
+                staking.zerosum_token = zerosum_token;
+            }
+            if let Some(reward_contract) = reward_contract {
+                staking.reward_contract = reward_contract;
+            }
+            if let Some(collector_contract) = collector_contract {
+                staking.collector_contract = collector_contract;
+            }
+            if let Some(min_bond) = min_bond {
+                staking.min_bond = min_bond;
+            }
+            if let Some(feed_reward) = feed_reward {
+                staking.feed_reward = feed_reward;
+            }
+            if let Some(reveal_window) = reveal_window {
+                staking.reveal_window = reveal_window;
+            }
+            if let Some(slash_rate) = slash_rate {
+                staking.slash_rate = slash_rate;
+            }
+            if let Some(feed_interval) = feed_interval {
+                staking.feed_interval = feed_interval;
+            }
+            execute_update_staking(deps, info, staking)
+        },
+        ExecuteMsg::Unbond { amount } => execute_unbond(deps, env, info, amount),
+        ExecuteMsg::SlashMissed { height, feeder } => execute_slash_missed(deps, env, height, feeder),
+        ExecuteMsg::PruneSeeds { before_height } => execute_prune_seeds(deps, env, info, before_height),
+    }
+}
+
+fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg) {
+        Ok(Cw20HookMsg::Bond {}) => {
+            execute_bond(deps, env, info.sender, Addr::unchecked(cw20_msg.sender), cw20_msg.amount)
+        },
+        Err(err) => Err(ContractError::Std(err)),
     }
 }
 
-pub fn execute_update_state(deps: DepsMut, info: MessageInfo, address: Addr) -> Result<Response, ContractError> {
+pub fn execute_update_state(deps: DepsMut, info: MessageInfo, gov_contract: Option<Addr>, quorum: Option<u32>, mode: Option<RandomMode>,
+    block_entropy: Option<bool>) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
     if info.sender != state.gov_contract {
-        state.gov_contract = address.clone();
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(gov_contract) = gov_contract {
+        state.gov_contract = gov_contract;
+    }
+    if let Some(quorum) = quorum {
+        // a seed could never finalize with no reveal needed or more than the feeders can give
+        let feeder_count = FEEDERS.keys(deps.storage, None, None, Order::Ascending).count();
+        if quorum == 0 || quorum as usize > feeder_count {
+            return Err(ContractError::InvalidQuorum {});
+        }
+        state.quorum = quorum;
+    }
+    if let Some(mode) = mode {
+        state.mode = mode;
+    }
+    if let Some(block_entropy) = block_entropy {
+        state.block_entropy = block_entropy;
     }
     STATE.save(deps.storage, &state)?;
     Ok(Response::new()
         .add_attribute("method", "update_state")
-        .add_attribute("gov_contract", address.to_string()))
+        .add_attribute("gov_contract", state.gov_contract.to_string())
+        .add_attribute("quorum", state.quorum.to_string())
+        .add_attribute("block_entropy", state.block_entropy.to_string()))
 }
 
 pub fn execute_add_feeder(deps: DepsMut, info: MessageInfo, address: Addr) -> Result<Response, ContractError> {
@@ -73,6 +188,9 @@ pub fn execute_remove_feeder(deps: DepsMut, info: MessageInfo, address: Addr) ->
     if info.sender != state.gov_contract {
         return Err(ContractError::Unauthorized {});
     }
+    if !keeps_quorum(deps.as_ref(), &address)? {
+        return Err(ContractError::InvalidQuorum {});
+    }
     FEEDERS.remove(deps.storage, address.clone());
     Ok(Response::new()
         .add_attribute("method", "remove_feeder")
@@ -94,7 +212,7 @@ pub fn execute_feed(deps: DepsMut, env: Env, info: MessageInfo, height: Option<u
     if !FEEDERS.has(deps.storage, info.sender) {
         return Err(ContractError::NotFeeder {})
     }
-    if SEEDS.has(deps.storage, height_key) {
+    if SEEDS.has(deps.storage, height_key) || SEED_ROUNDS.has(deps.storage, height_key) {
         return Err(ContractError::AlreadyExist {})
     }
 
@@ -105,12 +223,556 @@ pub fn execute_feed(deps: DepsMut, env: Env, info: MessageInfo, height: Option<u
         .add_attribute("seed", seed))
 }
 
+pub fn execute_commit_seed(deps: DepsMut, env: Env, info: MessageInfo, height: u64, hash: Binary) -> Result<Response, ContractError> {
+    if !FEEDERS.has(deps.storage, info.sender.clone()) {
+        return Err(ContractError::NotFeeder {})
+    }
+    // the seed has to be fixed before any bet on the height can be resolved
+    if height <= env.block.height {
+        return Err(ContractError::InvalidHeight {})
+    }
+    if SEEDS.has(deps.storage, height) || COMMITMENTS.has(deps.storage, (height, info.sender.clone())) {
+        return Err(ContractError::AlreadyExist {})
+    }
+
+    COMMITMENTS.save(deps.storage, (height, info.sender.clone()), &Commitment {
+        hash: hash.clone(),
+        committed_height: env.block.height,
+        seed: None,
+    })?;
+    SEED_ROUNDS.update(deps.storage, height, |prev| -> StdResult<_> {
+        let mut round = prev.unwrap_or(SeedRound {
+            commitments: 0,
+            reveals: 0,
+            last_committed_height: 0,
+        });
+        round.commitments += 1;
+        round.last_committed_height = env.block.height;
+        Ok(round)
+    })?;
+    FEEDER_INFOS.update(deps.storage, info.sender.clone(), |prev| -> StdResult<_> {
+        let mut feeder_info = prev.unwrap_or_default();
+        feeder_info.pending += 1;
+        Ok(feeder_info)
+    })?;
+    Ok(Response::new()
+        .add_attribute("method", "commit_seed")
+        .add_attribute("height", height.to_string())
+        .add_attribute("hash", hash.to_base64()))
+}
+
+pub fn execute_reveal_seed(mut deps: DepsMut, env: Env, info: MessageInfo, height: u64, seed: String) -> Result<Response, ContractError> {
+    let mut commitment = COMMITMENTS.may_load(deps.storage, (height, info.sender.clone()))?.ok_or(ContractError::NotExist {})?;
+    if commitment.seed.is_some() {
+        return Err(ContractError::AlreadyExist {})
+    }
+    // revealing early would leak the outcome of the bets waiting for the height
+    if env.block.height < height {
+        return Err(ContractError::InvalidHeight {})
+    }
+    if sha_256(seed.as_bytes()) != commitment.hash.as_slice() {
+        return Err(ContractError::SeedMismatch {})
+    }
+
+    commitment.seed = Some(seed.clone());
+    COMMITMENTS.save(deps.storage, (height, info.sender.clone()), &commitment)?;
+    let mut round = SEED_ROUNDS.load(deps.storage, height)?;
+    round.reveals += 1;
+    SEED_ROUNDS.save(deps.storage, height, &round)?;
+
+    let mut feeder_info = FEEDER_INFOS.may_load(deps.storage, info.sender.clone())?.unwrap_or_default();
+    feeder_info.pending = feeder_info.pending.saturating_sub(1);
+    if height > feeder_info.last_fed_height {
+        feeder_info.last_fed_height = height;
+    }
+    FEEDER_INFOS.save(deps.storage, info.sender.clone(), &feeder_info)?;
+
+    let finalized = try_finalize(deps.branch(), &env, height)?;
+    let reward_msgs = match finalized {
+        true => feed_reward_msgs(deps.as_ref(), height)?,
+        false => vec![],
+    };
+    Ok(Response::new().add_messages(reward_msgs)
+        .add_attribute("method", "reveal_seed")
+        .add_attribute("height", height.to_string())
+        .add_attribute("seed", seed)
+        .add_attribute("finalized", finalized.to_string()))
+}
+
+pub fn execute_update_drand(deps: DepsMut, env: Env, info: MessageInfo, pubkey: Binary, genesis_time: u64, period: u64) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+    // the checkpoint rounds divide by the period and count from the genesis
+    if period == 0 || genesis_time == 0 || genesis_time > env.block.time.seconds() || !drand::valid_pubkey(pubkey.as_slice()) {
+        return Err(ContractError::InvalidDrand {});
+    }
+    DRAND.save(deps.storage, &DrandConfig {
+        pubkey,
+        genesis_time,
+        period,
+    })?;
+    Ok(Response::new()
+        .add_attribute("method", "update_drand")
+        .add_attribute("genesis_time", genesis_time.to_string())
+        .add_attribute("period", period.to_string()))
+}
+
+pub fn execute_add_beacon(deps: DepsMut, env: Env, round: u64, previous_signature: Binary, signature: Binary) -> Result<Response, ContractError> {
+    let config = DRAND.may_load(deps.storage)?.ok_or(ContractError::NotExist {})?;
+    if BEACONS.has(deps.storage, round) {
+        return Err(ContractError::AlreadyExist {})
+    }
+    if !drand::verify(config.pubkey.as_slice(), round, previous_signature.as_slice(), signature.as_slice()) {
+        return Err(ContractError::InvalidBeacon {})
+    }
+    BEACONS.save(deps.storage, round, &Binary::from(drand::randomness(signature.as_slice())))?;
+
+    // a round published after this block can not be known by bets made up to this height
+    let checkpoint_round = drand::round_after(config.genesis_time, config.period, env.block.time.seconds());
+    if !CHECKPOINTS.has(deps.storage, env.block.height) {
+        CHECKPOINTS.save(deps.storage, env.block.height, &checkpoint_round)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "add_beacon")
+        .add_attribute("round", round.to_string())
+        .add_attribute("checkpoint_round", checkpoint_round.to_string()))
+}
+
+pub fn execute_request_randomness(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    request_id: u64,
+    resolve_height: u64,
+    entropy: Option<Vec<u8>>,
+    callback_msg: Binary,
+) -> Result<Response, ContractError> {
+    if resolve_height <= env.block.height {
+        return Err(ContractError::InvalidHeight {})
+    }
+    let id = next_request_index(deps.storage)?;
+    REQUESTS.save(deps.storage, id, &RandomnessRequest {
+        requester: info.sender.clone(),
+        request_id,
+        resolve_height,
+        entropy,
+        callback_msg,
+        status: RequestStatus::Pending,
+        placed_height: Some(env.block.height),
+    })?;
+    REQUEST_QUEUE.save(deps.storage, (resolve_height, id), &true)?;
+    Ok(Response::new()
+        .add_attribute("method", "request_randomness")
+        .add_attribute("id", id.to_string())
+        .add_attribute("requester", info.sender)
+        .add_attribute("request_id", request_id.to_string())
+        .add_attribute("resolve_height", resolve_height.to_string()))
+}
+
+pub fn execute_dispatch_requests(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let msgs = dispatch_requests(deps, &env)?;
+    Ok(Response::new()
+        .add_attribute("method", "dispatch_requests")
+        .add_attribute("count", msgs.len().to_string())
+        .add_submessages(msgs))
+}
+
+pub fn execute_retry_request(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let mut request = REQUESTS.may_load(deps.storage, id)?.ok_or(ContractError::NotExist {})?;
+    if request.status != RequestStatus::Failed {
+        return Err(ContractError::InvalidStatus {})
+    }
+    let msg = callback_msg(deps.as_ref(), &env, id, &request)?.ok_or(ContractError::NotFed {})?;
+    request.status = RequestStatus::Dispatched;
+    REQUESTS.save(deps.storage, id, &request)?;
+    FAILED_REQUESTS.remove(deps.storage, (request.resolve_height, id));
+    Ok(Response::new().add_submessage(msg)
+        .add_attribute("method", "retry_request")
+        .add_attribute("id", id.to_string()))
+}
+
+// callbacks of the queued requests whose seed is available, oldest height first.
+// the scan goes on after the last key it looked at, so the requests still waiting for a seed
+// do not hold back the later ones. it starts over once it reaches the block height
+fn dispatch_requests(deps: DepsMut, env: &Env) -> StdResult<Vec<SubMsg>> {
+    let start = DISPATCH_CURSOR.may_load(deps.storage)?.map(|key| Bound::ExclusiveRaw(key.joined_key()));
+    let queued: Vec<(u64, u64)> = REQUEST_QUEUE.keys(deps.storage, start, None, Order::Ascending)
+        .take_while(|key| match key {
+            Ok((resolve_height, _)) => *resolve_height <= env.block.height,
+            Err(_) => true,
+        })
+        .take(MAX_SCAN)
+        .collect::<StdResult<Vec<(u64, u64)>>>()?;
+
+    let mut msgs: Vec<SubMsg> = vec![];
+    let mut scanned = 0;
+    for (resolve_height, id) in queued.iter() {
+        if msgs.len() >= MAX_DISPATCH {
+            break;
+        }
+        scanned += 1;
+        let mut request = REQUESTS.load(deps.storage, *id)?;
+        let msg = match callback_msg(deps.as_ref(), env, *id, &request)? {
+            Some(msg) => msg,
+            None => continue,
+        };
+        request.status = RequestStatus::Dispatched;
+        REQUESTS.save(deps.storage, *id, &request)?;
+        REQUEST_QUEUE.remove(deps.storage, (*resolve_height, *id));
+        msgs.push(msg);
+    }
+
+    if scanned == MAX_SCAN || scanned < queued.len() {
+        DISPATCH_CURSOR.save(deps.storage, &queued[scanned - 1])?;
+    } else {
+        DISPATCH_CURSOR.remove(deps.storage);
+    }
+    Ok(msgs)
+}
+
+// a failing callback is reverted on its own and marked in `reply`
+fn callback_msg(deps: Deps, env: &Env, id: u64, request: &RandomnessRequest) -> StdResult<Option<SubMsg>> {
+    let seed = match query_final_seed(deps, env.clone(), request.resolve_height, request.placed_height)? {
+        Some(seed) => seed,
+        None => return Ok(None),
+    };
+    let mut rng = derive_prng(&seed, request.resolve_height, request.entropy.clone());
+    let callback = RandomnessCallback {
+        request_id: request.request_id,
+        resolve_height: request.resolve_height,
+        randomness: Binary::from(rng.rand_bytes()),
+        callback_msg: request.callback_msg.clone(),
+    };
+    Ok(Some(SubMsg::reply_on_error(callback.into_cosmos_msg(request.requester.clone())?, id)))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let mut request = REQUESTS.load(deps.storage, msg.id)?;
+    request.status = RequestStatus::Failed;
+    REQUESTS.save(deps.storage, msg.id, &request)?;
+    FAILED_REQUESTS.save(deps.storage, (request.resolve_height, msg.id), &true)?;
+    Ok(Response::new()
+        .add_attribute("method", "reply")
+        .add_attribute("id", msg.id.to_string())
+        .add_attribute("status", "failed"))
+}
+
+pub fn execute_finalize_seed(mut deps: DepsMut, env: Env, height: u64) -> Result<Response, ContractError> {
+    if !SEED_ROUNDS.has(deps.storage, height) {
+        return Err(ContractError::NotExist {});
+    }
+    if SEEDS.has(deps.storage, height) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    if !try_finalize(deps.branch(), &env, height)? {
+        return Err(ContractError::NotFed {});
+    }
+    Ok(Response::new().add_messages(feed_reward_msgs(deps.as_ref(), height)?)
+        .add_attribute("method", "finalize_seed")
+        .add_attribute("height", height.to_string()))
+}
+
+// commitments are closed at the height, so the seed is final once every remaining commitment is revealed.
+// once the reveal window closed the quorum is enough, the commitments left unrevealed are dropped and stay slashable
+fn try_finalize(deps: DepsMut, env: &Env, height: u64) -> StdResult<bool> {
+    let state = STATE.load(deps.storage)?;
+    let mut round = SEED_ROUNDS.load(deps.storage, height)?;
+    if SEEDS.has(deps.storage, height) || round.reveals < state.quorum {
+        return Ok(false);
+    }
+    if round.reveals != round.commitments {
+        if env.block.height <= height + reveal_window(deps.storage)? {
+            return Ok(false);
+        }
+        let withheld: Vec<Addr> = COMMITMENTS.prefix(height).range(deps.storage, None, None, Order::Ascending)
+            .filter(|item| !matches!(item, Ok((_, commitment)) if commitment.seed.is_some()))
+            .map(|item| item.map(|(feeder, _)| feeder))
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for feeder in withheld {
+            COMMITMENTS.remove(deps.storage, (height, feeder.clone()));
+            WITHHELD.save(deps.storage, (height, feeder), &true)?;
+            round.commitments -= 1;
+        }
+        SEED_ROUNDS.save(deps.storage, height, &round)?;
+    }
+    let final_seed = aggregate_seed(&contributions(deps.as_ref(), height, None)?);
+    SEEDS.save(deps.storage, height, &final_seed)?;
+    Ok(true)
+}
+
+// the contributors of a finalized seed are rewarded at the assigned heights only
+fn feed_reward_msgs(deps: Deps, height: u64) -> StdResult<Vec<CosmosMsg>> {
+    let staking = match STAKING.may_load(deps.storage)? {
+        Some(staking) => staking,
+        None => return Ok(vec![]),
+    };
+    if staking.feed_reward.is_zero() || staking.reward_contract.as_str().is_empty() || !is_assigned(&staking, height) {
+        return Ok(vec![]);
+    }
+    contributions(deps, height, None)?.into_iter()
+        .map(|(feeder, _)| reward_msg(staking.reward_contract.clone(), feeder, staking.feed_reward))
+        .collect()
+}
+
+fn is_assigned(staking: &StakingConfig, height: u64) -> bool {
+    staking.feed_interval > 0 && height % staking.feed_interval == 0
+}
+
+// an assigned height the feeder did not reveal can be slashed until twice the reveal window passed
+fn in_slash_window(staking: &StakingConfig, height: u64, block_height: u64) -> bool {
+    block_height > height.saturating_add(staking.reveal_window)
+        && block_height <= height.saturating_add(staking.reveal_window.saturating_mul(2))
+}
+
+fn reveal_window(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(STAKING.may_load(storage)?.map(|staking| staking.reveal_window).unwrap_or(DEFAULT_REVEAL_WINDOW))
+}
+
+// whether the feeders left without `feeder` still meet the quorum
+fn keeps_quorum(deps: Deps, feeder: &Addr) -> StdResult<bool> {
+    if !FEEDERS.has(deps.storage, feeder.clone()) {
+        return Ok(true);
+    }
+    let state = STATE.load(deps.storage)?;
+    let feeder_count = FEEDERS.keys(deps.storage, None, None, Order::Ascending).count();
+    Ok(feeder_count > state.quorum as usize)
+}
+
+pub fn execute_update_staking(deps: DepsMut, info: MessageInfo, staking: StakingConfig) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+    STAKING.save(deps.storage, &staking)?;
+    Ok(Response::new()
+        .add_attribute("method", "update_staking")
+        .add_attribute("min_bond", staking.min_bond)
+        .add_attribute("feed_reward", staking.feed_reward))
+}
+
+pub fn execute_bond(deps: DepsMut, env: Env, token: Addr, feeder: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    let staking = STAKING.may_load(deps.storage)?.ok_or(ContractError::NotExist {})?;
+    if token != staking.zerosum_token {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut feeder_info = FEEDER_INFOS.may_load(deps.storage, feeder.clone())?.unwrap_or_default();
+    if feeder_info.bond.is_zero() {
+        feeder_info.bonded_height = env.block.height;
+    }
+    feeder_info.bond += amount;
+    FEEDER_INFOS.save(deps.storage, feeder.clone(), &feeder_info)?;
+
+    let registered = feeder_info.bond >= staking.min_bond;
+    if registered {
+        FEEDERS.save(deps.storage, feeder.clone(), &true)?;
+    }
+    Ok(Response::new()
+        .add_attribute("method", "bond")
+        .add_attribute("feeder", feeder)
+        .add_attribute("bond", feeder_info.bond)
+        .add_attribute("registered", registered.to_string()))
+}
+
+pub fn execute_unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Option<Uint128>) -> Result<Response, ContractError> {
+    let staking = STAKING.may_load(deps.storage)?.ok_or(ContractError::NotExist {})?;
+    let mut feeder_info = FEEDER_INFOS.may_load(deps.storage, info.sender.clone())?.unwrap_or_default();
+    // a bond backs the commitments still to be revealed
+    if feeder_info.pending > 0 {
+        return Err(ContractError::PendingCommitment {});
+    }
+    // and the assigned heights it can still be slashed for
+    if FEEDERS.has(deps.storage, info.sender.clone()) && staking.feed_interval > 0 {
+        let window = staking.reveal_window.saturating_mul(2);
+        let first = env.block.height.saturating_sub(window).max(feeder_info.bonded_height + 1);
+        let mut height = first + (staking.feed_interval - first % staking.feed_interval) % staking.feed_interval;
+        while height < env.block.height {
+            if in_slash_window(&staking, height, env.block.height)
+                && !SLASHED.has(deps.storage, (height, info.sender.clone()))
+                && !COMMITMENTS.has(deps.storage, (height, info.sender.clone())) {
+                return Err(ContractError::PendingCommitment {});
+            }
+            height += staking.feed_interval;
+        }
+    }
+    let amount = amount.unwrap_or(feeder_info.bond);
+    if amount.is_zero() || amount > feeder_info.bond {
+        return Err(ContractError::Insufficient {});
+    }
+    feeder_info.bond -= amount;
+    FEEDER_INFOS.save(deps.storage, info.sender.clone(), &feeder_info)?;
+    if feeder_info.bond < staking.min_bond {
+        if !keeps_quorum(deps.as_ref(), &info.sender)? {
+            return Err(ContractError::InvalidQuorum {});
+        }
+        FEEDERS.remove(deps.storage, info.sender.clone());
+    }
+
+    let msg = token_asset(staking.zerosum_token, amount).into_msg(&deps.querier, info.sender)?;
+    Ok(Response::new().add_message(msg)
+        .add_attribute("method", "unbond")
+        .add_attribute("amount", amount)
+        .add_attribute("bond", feeder_info.bond))
+}
+
+pub fn execute_slash_missed(mut deps: DepsMut, env: Env, height: u64, feeder: Addr) -> Result<Response, ContractError> {
+    let staking = STAKING.may_load(deps.storage)?.ok_or(ContractError::NotExist {})?;
+    if env.block.height <= height + staking.reveal_window {
+        return Err(ContractError::NotMissed {});
+    }
+    if SLASHED.has(deps.storage, (height, feeder.clone())) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    let mut feeder_info = FEEDER_INFOS.may_load(deps.storage, feeder.clone())?.unwrap_or_default();
+
+    let unrevealed = match COMMITMENTS.may_load(deps.storage, (height, feeder.clone()))? {
+        Some(commitment) if commitment.seed.is_some() => return Err(ContractError::NotMissed {}),
+        Some(_) => true,
+        // dropped when the seed was finalized without it, kept through the pruning
+        None if WITHHELD.has(deps.storage, (height, feeder.clone())) => {
+            WITHHELD.remove(deps.storage, (height, feeder.clone()));
+            feeder_info.pending = feeder_info.pending.saturating_sub(1);
+            false
+        },
+        // the commitments of a pruned height are gone, a reveal can not be told from a skip
+        None if height < PRUNED_BEFORE.may_load(deps.storage)?.unwrap_or_default() => return Err(ContractError::NotMissed {}),
+        None => {
+            if !is_assigned(&staking, height) || !in_slash_window(&staking, height, env.block.height)
+                || !FEEDERS.has(deps.storage, feeder.clone()) || feeder_info.bonded_height >= height {
+                return Err(ContractError::NotMissed {});
+            }
+            false
+        },
+    };
+    SLASHED.save(deps.storage, (height, feeder.clone()), &true)?;
+
+    // the withheld contribution is dropped so the others can still finalize the seed
+    if unrevealed {
+        COMMITMENTS.remove(deps.storage, (height, feeder.clone()));
+        SEED_ROUNDS.update(deps.storage, height, |prev| -> StdResult<_> {
+            let mut round = prev.ok_or_else(|| StdError::not_found("SeedRound"))?;
+            round.commitments -= 1;
+            Ok(round)
+        })?;
+        feeder_info.pending = feeder_info.pending.saturating_sub(1);
+    }
+
+    let bond = feeder_info.bond;
+    let slash_amount = bond * staking.slash_rate;
+    feeder_info.bond = bond - slash_amount;
+    feeder_info.missed += 1;
+    FEEDER_INFOS.save(deps.storage, feeder.clone(), &feeder_info)?;
+    // a feeder the quorum can not do without stays registered, gov replaces it
+    if !staking.min_bond.is_zero() && bond >= staking.min_bond && feeder_info.bond < staking.min_bond
+        && keeps_quorum(deps.as_ref(), &feeder)? {
+        FEEDERS.remove(deps.storage, feeder.clone());
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !slash_amount.is_zero() {
+        msgs.push(token_asset(staking.zerosum_token, slash_amount).into_msg(&deps.querier, staking.collector_contract)?);
+    }
+    let finalized = unrevealed && try_finalize(deps.branch(), &env, height)?;
+    if finalized {
+        msgs.extend(feed_reward_msgs(deps.as_ref(), height)?);
+    }
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "slash_missed")
+        .add_attribute("feeder", feeder)
+        .add_attribute("height", height.to_string())
+        .add_attribute("slash_amount", slash_amount)
+        .add_attribute("finalized", finalized.to_string()))
+}
+
+pub fn execute_prune_seeds(deps: DepsMut, env: Env, info: MessageInfo, before_height: u64) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if info.sender != state.gov_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+    // the bets still open and the heights still slashable keep their seeds and commitments
+    let keep_height = env.block.height
+        .saturating_sub(MIN_SEED_AGE)
+        .min(env.block.height.saturating_sub(reveal_window(deps.storage)?.saturating_mul(2)));
+    let mut prune_height = before_height.min(keep_height);
+    // an open request takes the seed of its own height. both queues are keyed by the resolve height first,
+    // so their first key is the lowest height of all their requests
+    for queue in [REQUEST_QUEUE, FAILED_REQUESTS] {
+        if let Some((resolve_height, _)) = queue.keys(deps.storage, None, None, Order::Ascending).next().transpose()? {
+            prune_height = prune_height.min(resolve_height);
+        }
+    }
+    if prune_height > PRUNED_BEFORE.may_load(deps.storage)?.unwrap_or_default() {
+        PRUNED_BEFORE.save(deps.storage, &prune_height)?;
+    }
+
+    let end = Some(Bound::ExclusiveRaw(prune_height.to_be_bytes().to_vec()));
+    let heights: Vec<u64> = SEEDS.keys(deps.storage, None, end, Order::Ascending)
+        .take(MAX_PRUNE)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for height in heights.iter() {
+        SEEDS.remove(deps.storage, *height);
+        SEED_ROUNDS.remove(deps.storage, *height);
+        let feeders: Vec<Addr> = COMMITMENTS.prefix(*height).keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for feeder in feeders {
+            COMMITMENTS.remove(deps.storage, (*height, feeder));
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "prune_seeds")
+        .add_attribute("prune_height", prune_height.to_string())
+        .add_attribute("pruned", heights.len().to_string()))
+}
+
+// revealed contributions of the height in feeder order, only the ones committed before `placed_height` when given
+fn contributions(deps: Deps, height: u64, placed_height: Option<u64>) -> StdResult<Vec<(Addr, Commitment)>> {
+    COMMITMENTS.prefix(height).range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, commitment)) => commitment.seed.is_some() && match placed_height {
+                Some(placed_height) => commitment.committed_height < placed_height,
+                None => true,
+            },
+            Err(_) => true,
+        })
+        .collect()
+}
+
+// hash of the contributions concatenated in feeder order
+fn aggregate_seed(contributions: &[(Addr, Commitment)]) -> String {
+    let mut data: Vec<u8> = vec![];
+    for (_, commitment) in contributions.iter() {
+        data.extend(commitment.seed.clone().unwrap_or_default().as_bytes());
+    }
+    Binary::from(sha_256(&data)).to_base64()
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Seed { height } => to_binary(&query_seed(deps, height)?),
-        QueryMsg::RandomOne { height, entropy, max_value } => to_binary(&query_random(deps, height, entropy, 0, max_value)?),
-        QueryMsg::RandomBetween { height, entropy, min_value, max_value } => to_binary(&query_random(deps, height, entropy, min_value, max_value)?),
+        QueryMsg::SeedAt { height } => to_binary(&SEEDS.may_load(deps.storage, height)?),
+        QueryMsg::Seeds { start_after, limit } => to_binary(&query_seeds(deps, start_after, limit)?),
+        QueryMsg::RandomOne { height, entropy, max_value, max_lag, placed_height } =>
+            to_binary(&query_random_one(deps, env, height, entropy, max_value, max_lag, placed_height)?),
+        QueryMsg::RandomBetween { height, entropy, min_value, max_value, placed_height } =>
+            to_binary(&query_random(deps, env, height, entropy, min_value, max_value, placed_height)?),
+        QueryMsg::RandomMany { height, entropy, count, min_value, max_value, placed_height } =>
+            to_binary(&query_random_many(deps, env, height, entropy, count, min_value, max_value, placed_height)?),
+        QueryMsg::Shuffle { height, entropy, n, placed_height } => to_binary(&query_shuffle(deps, env, height, entropy, n, placed_height)?),
+        QueryMsg::WeightedPick { height, entropy, weights, placed_height } =>
+            to_binary(&query_weighted_pick(deps, env, height, entropy, weights, placed_height)?),
+        QueryMsg::VerifyOutcome { height, entropy, min, max, placed_height } =>
+            to_binary(&query_verify_outcome(deps, env, height, entropy, min, max, placed_height)?),
+        QueryMsg::Commitment { height } => to_binary(&query_commitment(deps, height)?),
+        QueryMsg::Beacon { round } => to_binary(&BEACONS.may_load(deps.storage, round)?),
+        QueryMsg::DrandRound { height } => to_binary(&query_drand_round(deps, height)?),
+        QueryMsg::Request { id } => to_binary(&query_request(deps, id)?),
+        QueryMsg::Feeder { address } => to_binary(&query_feeder(deps, address)?),
+        QueryMsg::Feeders { start_after, limit } => to_binary(&query_feeders(deps, start_after, limit)?),
+        QueryMsg::PendingRequests { start_after, limit } => to_binary(&query_pending_requests(deps, start_after, limit)?),
     }
 }
 
@@ -127,27 +789,350 @@ fn query_seed(deps: Deps, height: u64) -> StdResult<Option<String>> {
     Ok(Some(seed))
 }
 
-fn query_random(deps: Deps, height: u64, entropy: Option<Vec<u8>>, min_value: u32, max_value: u32) -> StdResult<Option<u32>> {
-    let start = Some(Bound::InclusiveRaw(height.to_be_bytes().to_vec()));
-    let seed: String = SEEDS.range(deps.storage, start, None, Order::Ascending).take(1).map(|seed| {
-        let (_, value) = seed.unwrap();
-        value
-    }).collect();
-    if seed == String::default() {
+fn query_seeds(deps: Deps, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<SeedResponse>> {
+    let start = start_after.map(|height| Bound::ExclusiveRaw(height.to_be_bytes().to_vec()));
+    SEEDS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (height, seed) = item?;
+        Ok(SeedResponse { height, seed })
+    }).collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_random_one(
+    deps: Deps,
+    env: Env,
+    height: u64,
+    entropy: Option<Vec<u8>>,
+    max_value: u32,
+    max_lag: Option<u64>,
+    placed_height: Option<u64>,
+) -> StdResult<Option<u32>> {
+    // checked before any value is derived from the seed
+    if let Some(max_lag) = max_lag {
+        let seed_height = match query_seed_height(deps, env.clone(), height)? {
+            Some(seed_height) => seed_height,
+            None => return Ok(None),
+        };
+        if seed_height > height + max_lag {
+            return Err(StdError::generic_err(format!("seed of height {} is {} blocks after the height", seed_height, seed_height - height)));
+        }
+    }
+    query_random(deps, env, height, entropy, 0, max_value, placed_height)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_random(
+    deps: Deps,
+    env: Env,
+    height: u64,
+    entropy: Option<Vec<u8>>,
+    min_value: u32,
+    max_value: u32,
+    placed_height: Option<u64>,
+) -> StdResult<Option<u32>> {
+    let rng = query_prng(deps, env, height, entropy, placed_height)?;
+    Ok(rng.map(|mut rng| rng.random_between(min_value, max_value)))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_random_many(
+    deps: Deps,
+    env: Env,
+    height: u64,
+    entropy: Option<Vec<u8>>,
+    count: u32,
+    min_value: u32,
+    max_value: u32,
+    placed_height: Option<u64>,
+) -> StdResult<Option<Vec<u32>>> {
+    if count > MAX_DRAWS {
+        return Err(StdError::generic_err("too many draws"));
+    }
+    let rng = query_prng(deps, env, height, entropy, placed_height)?;
+    Ok(rng.map(|mut rng| (0..count).map(|_| rng.random_between(min_value, max_value)).collect()))
+}
+
+fn query_shuffle(deps: Deps, env: Env, height: u64, entropy: Option<Vec<u8>>, n: u32, placed_height: Option<u64>) -> StdResult<Option<Vec<u32>>> {
+    if n > MAX_DRAWS {
+        return Err(StdError::generic_err("too many draws"));
+    }
+    let rng = query_prng(deps, env, height, entropy, placed_height)?;
+    Ok(rng.map(|mut rng| rng.shuffle(n)))
+}
+
+fn query_weighted_pick(deps: Deps, env: Env, height: u64, entropy: Option<Vec<u8>>, weights: Vec<u32>, placed_height: Option<u64>) -> StdResult<Option<u32>> {
+    if weights.iter().all(|weight| *weight == 0) {
+        return Err(StdError::generic_err("no weight"));
+    }
+    let rng = query_prng(deps, env, height, entropy, placed_height)?;
+    Ok(rng.and_then(|mut rng| rng.weighted_pick(&weights)))
+}
+
+fn query_prng(deps: Deps, env: Env, height: u64, entropy: Option<Vec<u8>>, placed_height: Option<u64>) -> StdResult<Option<Prng>> {
+    let seed = query_final_seed(deps, env, height, placed_height)?;
+    Ok(seed.map(|seed| derive_prng(&seed, height, entropy)))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_verify_outcome(
+    deps: Deps,
+    env: Env,
+    height: u64,
+    entropy: Option<Vec<u8>>,
+    min: u32,
+    max: u32,
+    placed_height: Option<u64>,
+) -> StdResult<Option<VerifyOutcomeResponse>> {
+    let state = STATE.load(deps.storage)?;
+    let seed = match query_final_seed(deps, env.clone(), height, placed_height)? {
+        Some(seed) => seed,
+        None => return Ok(None),
+    };
+    let seed_height = match state.mode {
+        RandomMode::Drand => None,
+        _ => query_seed_round(deps, env, height)?,
+    };
+    let contributions = match seed_height {
+        Some(seed_height) => contribution_responses(contributions(deps, seed_height, placed_height)?),
+        None => vec![],
+    };
+    let drand_round = match state.mode {
+        RandomMode::Feeder => None,
+        _ => query_drand_round(deps, height)?,
+    };
+    let drand_randomness = match drand_round {
+        Some(round) => BEACONS.may_load(deps.storage, round)?,
+        None => None,
+    };
+    let block_hash = if state.block_entropy {
+        Some(query_block_hash(&deps.querier, height)?)
+    } else {
+        None
+    };
+    let mut rng = derive_prng(&seed, height, entropy.clone());
+    Ok(Some(VerifyOutcomeResponse {
+        height,
+        seed,
+        seed_height,
+        contributions,
+        drand_round,
+        drand_randomness,
+        block_hash,
+        entropy: Binary::from(derive_entropy(height, entropy)),
+        chacha_seed: Binary::from(rng.chacha_seed()),
+        value: rng.random_between(min, max),
+    }))
+}
+
+fn query_feeder(deps: Deps, address: Addr) -> StdResult<FeederResponse> {
+    let feeder_info = FEEDER_INFOS.may_load(deps.storage, address.clone())?.unwrap_or_default();
+    Ok(FeederResponse {
+        address,
+        bond: feeder_info.bond,
+        last_fed_height: feeder_info.last_fed_height,
+        missed: feeder_info.missed,
+        pending: feeder_info.pending,
+    })
+}
+
+// liveness of the registered feeders
+fn query_feeders(deps: Deps, start_after: Option<Addr>, limit: Option<u64>) -> StdResult<Vec<FeederResponse>> {
+    let start = start_after.map(|address| Bound::ExclusiveRaw(address.as_bytes().to_vec()));
+    FEEDERS.keys(deps.storage, start, None, Order::Ascending)
+        .take(limit.unwrap_or(20) as usize)
+        .map(|address| query_feeder(deps, address?))
+        .collect()
+}
+
+fn query_request(deps: Deps, id: u64) -> StdResult<RequestResponse> {
+    let request = REQUESTS.load(deps.storage, id)?;
+    Ok(request_response(id, request))
+}
+
+// requests not delivered yet, queued or failed
+fn query_pending_requests(deps: Deps, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<RequestResponse>> {
+    let start = start_after.map(|id| Bound::ExclusiveRaw(id.to_be_bytes().to_vec()));
+    REQUESTS.range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| !matches!(item, Ok((_, request)) if request.status == RequestStatus::Dispatched))
+        .take(limit.unwrap_or(20) as usize)
+        .map(|item| {
+            let (id, request) = item?;
+            Ok(request_response(id, request))
+        })
+        .collect()
+}
+
+fn request_response(id: u64, request: RandomnessRequest) -> RequestResponse {
+    RequestResponse {
+        id,
+        requester: request.requester,
+        request_id: request.request_id,
+        resolve_height: request.resolve_height,
+        status: request.status,
+    }
+}
+
+// the seed of the mode, with the hash of the target block mixed in when `block_entropy` is on.
+// the hash is known only once the block is committed, so a feeder can not tell the outcome from its own seed.
+fn query_final_seed(deps: Deps, env: Env, height: u64, placed_height: Option<u64>) -> StdResult<Option<String>> {
+    let state = STATE.load(deps.storage)?;
+    if state.block_entropy && height >= env.block.height {
         return Ok(None);
     }
-    let seed = seed.as_bytes();
-    let mut entropy_vec = height.to_be_bytes().to_vec();
-    entropy_vec.extend(entropy.unwrap_or_default());
-    let mut rng: Prng = Prng::new(&seed, &entropy_vec.as_slice());
-    Ok(Some(rng.random_between(min_value, max_value)))
+    let seed = match query_mode_seed(deps, env, height, placed_height)? {
+        Some(seed) => seed,
+        None => return Ok(None),
+    };
+    if !state.block_entropy {
+        return Ok(Some(seed));
+    }
+    let block_hash = query_block_hash(&deps.querier, height)?;
+    let mut data = seed.into_bytes();
+    data.extend(block_hash.into_bytes());
+    Ok(Some(Binary::from(sha_256(&data)).to_base64()))
+}
+
+fn query_mode_seed(deps: Deps, env: Env, height: u64, placed_height: Option<u64>) -> StdResult<Option<String>> {
+    let state = STATE.load(deps.storage)?;
+    match state.mode {
+        RandomMode::Feeder => query_committed_seed(deps, env, height, placed_height),
+        RandomMode::Drand => query_drand_seed(deps, height),
+        RandomMode::Mixed => {
+            let feeder_seed = query_committed_seed(deps, env, height, placed_height)?;
+            let drand_seed = query_drand_seed(deps, height)?;
+            match (feeder_seed, drand_seed) {
+                (Some(feeder_seed), Some(drand_seed)) => {
+                    let mut data = feeder_seed.into_bytes();
+                    data.extend(drand_seed.into_bytes());
+                    Ok(Some(Binary::from(sha_256(&data)).to_base64()))
+                },
+                _ => Ok(None),
+            }
+        },
+    }
+}
+
+// height the seed of the height was settled at, the later of the feeder round and drand checkpoint in mixed mode
+fn query_seed_height(deps: Deps, env: Env, height: u64) -> StdResult<Option<u64>> {
+    let state = STATE.load(deps.storage)?;
+    let start = Some(Bound::InclusiveRaw(height.to_be_bytes().to_vec()));
+    let checkpoint_height = || -> StdResult<Option<u64>> {
+        let checkpoint = CHECKPOINTS.keys(deps.storage, start.clone(), None, Order::Ascending).next().transpose()?;
+        Ok(checkpoint)
+    };
+    match state.mode {
+        RandomMode::Feeder => query_seed_round(deps, env, height),
+        RandomMode::Drand => checkpoint_height(),
+        RandomMode::Mixed => Ok(query_seed_round(deps, env, height)?.max(checkpoint_height()?)),
+    }
+}
+
+// round of the first checkpoint at or after the height
+fn query_drand_round(deps: Deps, height: u64) -> StdResult<Option<u64>> {
+    let start = Some(Bound::InclusiveRaw(height.to_be_bytes().to_vec()));
+    let checkpoint = CHECKPOINTS.range(deps.storage, start, None, Order::Ascending).next().transpose()?;
+    Ok(checkpoint.map(|(_, round)| round))
+}
+
+fn query_drand_seed(deps: Deps, height: u64) -> StdResult<Option<String>> {
+    let round = match query_drand_round(deps, height)? {
+        Some(round) => round,
+        None => return Ok(None),
+    };
+    let randomness = BEACONS.may_load(deps.storage, round)?;
+    Ok(randomness.map(|randomness| randomness.to_base64()))
+}
+
+// seed of the round of the height itself. an unfinished one is waited for,
+// skipping it would let a feeder withhold a contribution.
+// a feeder that saw the bet placed could pick its contribution, so the ones committed
+// at or after `placed_height` are left out and the rest has to meet the quorum on its own.
+fn query_committed_seed(deps: Deps, env: Env, height: u64, placed_height: Option<u64>) -> StdResult<Option<String>> {
+    let state = STATE.load(deps.storage)?;
+    let seed_height = match query_seed_round(deps, env, height)? {
+        Some(seed_height) => seed_height,
+        None => return Ok(None),
+    };
+    let seed = match SEEDS.may_load(deps.storage, seed_height)? {
+        Some(seed) => seed,
+        None => return Ok(None),
+    };
+    let placed_height = match placed_height {
+        Some(placed_height) => placed_height,
+        None => return Ok(Some(seed)),
+    };
+    match SEED_ROUNDS.may_load(deps.storage, seed_height)? {
+        Some(round) if round.last_committed_height >= placed_height => {
+            let contributions = contributions(deps, seed_height, Some(placed_height))?;
+            if (contributions.len() as u32) < state.quorum {
+                return Ok(None);
+            }
+            Ok(Some(aggregate_seed(&contributions)))
+        },
+        _ => Ok(Some(seed)),
+    }
+}
+
+// height of the round the committed seed of the height is taken from, never a later one
+fn query_seed_round(deps: Deps, env: Env, height: u64) -> StdResult<Option<u64>> {
+    let state = STATE.load(deps.storage)?;
+    match SEED_ROUNDS.may_load(deps.storage, height)? {
+        // commitments are closed and the quorum can no longer be met
+        Some(round) if round.commitments < state.quorum && height <= env.block.height => Ok(None),
+        Some(_) => Ok(Some(height)),
+        // fed by gov without commitments
+        None if SEEDS.has(deps.storage, height) => Ok(Some(height)),
+        None => Ok(None),
+    }
+}
+
+fn query_commitment(deps: Deps, height: u64) -> StdResult<CommitmentResponse> {
+    let state = STATE.load(deps.storage)?;
+    let mut committed: Vec<Addr> = vec![];
+    let mut contributors: Vec<Addr> = vec![];
+    for item in COMMITMENTS.prefix(height).range(deps.storage, None, None, Order::Ascending) {
+        let (feeder, commitment) = item?;
+        if commitment.seed.is_some() {
+            contributors.push(feeder.clone());
+        }
+        committed.push(feeder);
+    }
+    let missing: Vec<Addr> = FEEDERS.keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?
+        .into_iter()
+        .filter(|feeder| !contributors.contains(feeder))
+        .collect();
+
+    Ok(CommitmentResponse {
+        height,
+        committed,
+        contributors,
+        missing,
+        quorum: state.quorum,
+        finalized: SEED_ROUNDS.has(deps.storage, height) && SEEDS.has(deps.storage, height),
+        contributions: contribution_responses(contributions(deps, height, None)?),
+    })
+}
+
+fn contribution_responses(contributions: Vec<(Addr, Commitment)>) -> Vec<ContributionResponse> {
+    contributions.into_iter()
+        .map(|(feeder, commitment)| ContributionResponse {
+            feeder,
+            hash: commitment.hash,
+            seed: commitment.seed.unwrap_or_default(),
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, BlockInfo, ContractInfo, Timestamp};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{coins, from_binary, from_slice, BlockInfo, ContractInfo, ContractResult, OwnedDeps, Querier, QuerierResult, QueryRequest,
+        SystemError, SystemResult, Timestamp};
+    use std::collections::HashMap;
+    use std::marker::PhantomData;
+    use zerosum::querier::{Block, BlockInfoResposne, TendermintQuery, TendermintQueryWrapper};
 
     #[test]
     fn proper_initialization() {
@@ -192,9 +1177,11 @@ mod tests {
         let info = mock_info("creator", &vec![]);
         let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        let info = mock_info("creator", &vec![]);
-        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        for feeder in ["feeder", "feeder2"] {
+            let info = mock_info("creator", &vec![]);
+            let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked(feeder) };
+            let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
 
         let info = mock_info("anyone", &vec![]);
         let msg = ExecuteMsg::RemoveFeeder { address: Addr::unchecked("creator") };
@@ -207,6 +1194,15 @@ mod tests {
         let info = mock_info("creator", &vec![]);
         let msg = ExecuteMsg::RemoveFeeder { address: Addr::unchecked("feeder") };
         let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // the last feeder is needed for the quorum
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::RemoveFeeder { address: Addr::unchecked("feeder2") };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Err(ContractError::InvalidQuorum {}) => {}
+            _ => panic!("Must return error")
+        }
     }
 
     #[test]
@@ -322,43 +1318,865 @@ mod tests {
         let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
 
 
-        let res = query(deps.as_ref(), mock_env_height(1), QueryMsg::RandomOne { height: 0, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(1), QueryMsg::RandomOne { height: 0, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(23u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(2), QueryMsg::RandomOne { height: 1, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(2), QueryMsg::RandomOne { height: 1, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(26u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(3), QueryMsg::RandomOne { height: 2, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(3), QueryMsg::RandomOne { height: 2, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(55u32, value);
         
-        let res = query(deps.as_ref(), mock_env_height(4), QueryMsg::RandomOne { height: 4, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(4), QueryMsg::RandomOne { height: 4, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(83u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(5), QueryMsg::RandomOne { height: 5, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(5), QueryMsg::RandomOne { height: 5, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(83u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 6, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 6, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap();
         assert_eq!(67u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 100, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 100, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap_or(1000u32);
         assert_eq!(1000u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 300, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 300, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap_or(1000u32);
         assert_eq!(1000u32, value);
 
-        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 400, max_value: 99, entropy: None }).unwrap();
+        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::RandomOne { height: 400, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
         let value: u32 = from_binary(&res).unwrap_or(1000u32);
         assert_eq!(1000u32, value);
     }
 
+    #[test]
+    fn commit_reveal() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let seed = String::from("0xSEED___________10___________");
+        let hash = Binary::from(sha_256(seed.as_bytes()));
+
+        // too late to commit
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: hash.clone() };
+        let res = execute(deps.as_mut(), mock_env_height(10), info, msg);
+        match res {
+            Err(ContractError::InvalidHeight {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: hash.clone() };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        // too early to reveal
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: seed.clone() };
+        let res = execute(deps.as_mut(), mock_env_height(9), info, msg);
+        match res {
+            Err(ContractError::InvalidHeight {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: String::from("0xSEED___________11___________") };
+        let res = execute(deps.as_mut(), mock_env_height(10), info, msg);
+        match res {
+            Err(ContractError::SeedMismatch {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 10, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: seed.clone() };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 10, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert!(value.is_some());
+
+        // a height without its own round never takes the seed of a later one
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 8, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+    }
+
+    #[test]
+    fn quorum() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        for feeder in ["feeder1", "feeder2", "feeder3"] {
+            let info = mock_info("creator", &vec![]);
+            let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked(feeder) };
+            let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+        }
+
+        for quorum in [0, 4] {
+            let info = mock_info("creator", &vec![]);
+            let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: Some(quorum), mode: None, block_entropy: None };
+            let res = execute(deps.as_mut(), mock_env_height(0), info, msg);
+            match res {
+                Err(ContractError::InvalidQuorum {}) => {}
+                _ => panic!("Must return error")
+            }
+        }
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: Some(2), mode: None, block_entropy: None };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        for feeder in ["feeder2", "feeder1"] {
+            let info = mock_info(feeder, &vec![]);
+            let hash = Binary::from(sha_256(feeder.as_bytes()));
+            let msg = ExecuteMsg::CommitSeed { height: 10, hash };
+            let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+        }
+
+        let info = mock_info("feeder2", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: String::from("feeder2") };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::Commitment { height: 10 }).unwrap();
+        let commitment: CommitmentResponse = from_binary(&res).unwrap();
+        assert_eq!(CommitmentResponse {
+            height: 10,
+            committed: vec![Addr::unchecked("feeder1"), Addr::unchecked("feeder2")],
+            contributors: vec![Addr::unchecked("feeder2")],
+            missing: vec![Addr::unchecked("feeder1"), Addr::unchecked("feeder3")],
+            quorum: 2,
+            finalized: false,
+            contributions: vec![ContributionResponse {
+                feeder: Addr::unchecked("feeder2"),
+                hash: Binary::from(sha_256(b"feeder2")),
+                seed: String::from("feeder2"),
+            }],
+        }, commitment);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 10, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+
+        let info = mock_info("feeder1", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: String::from("feeder1") };
+        let _res = execute(deps.as_mut(), mock_env_height(11), info, msg).unwrap();
+
+        // contributions in feeder order
+        let expected = Binary::from(sha_256(b"feeder1feeder2")).to_base64();
+        assert_eq!(expected, SEEDS.load(deps.as_ref().storage, 10).unwrap());
+
+        let res = query(deps.as_ref(), mock_env_height(11), QueryMsg::RandomOne { height: 10, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert!(value.is_some());
+
+        // feeder3 withholds its contribution
+        for feeder in ["feeder1", "feeder2", "feeder3"] {
+            let info = mock_info(feeder, &vec![]);
+            let msg = ExecuteMsg::CommitSeed { height: 20, hash: Binary::from(sha_256(feeder.as_bytes())) };
+            let _res = execute(deps.as_mut(), mock_env_height(15), info, msg).unwrap();
+        }
+        for feeder in ["feeder1", "feeder2"] {
+            let info = mock_info(feeder, &vec![]);
+            let msg = ExecuteMsg::RevealSeed { height: 20, seed: String::from(feeder) };
+            let _res = execute(deps.as_mut(), mock_env_height(20), info, msg).unwrap();
+        }
+
+        // the reveal window is still open
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::FinalizeSeed { height: 20 };
+        let res = execute(deps.as_mut(), mock_env_height(30), info, msg.clone());
+        match res {
+            Err(ContractError::NotFed {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("anyone", &vec![]);
+        let _res = execute(deps.as_mut(), mock_env_height(31), info, msg).unwrap();
+        assert_eq!(Binary::from(sha_256(b"feeder1feeder2")).to_base64(), SEEDS.load(deps.as_ref().storage, 20).unwrap());
+        assert!(WITHHELD.has(deps.as_ref().storage, (20, Addr::unchecked("feeder3"))));
+
+        let res = query(deps.as_ref(), mock_env_height(31), QueryMsg::Commitment { height: 20 }).unwrap();
+        let commitment: CommitmentResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("feeder1"), Addr::unchecked("feeder2")], commitment.committed);
+        assert!(commitment.finalized);
+
+        // too late to reveal
+        let info = mock_info("feeder3", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 20, seed: String::from("feeder3") };
+        let res = execute(deps.as_mut(), mock_env_height(31), info, msg);
+        match res {
+            Err(ContractError::NotExist {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::RemoveFeeder { address: Addr::unchecked("feeder3") };
+        let _res = execute(deps.as_mut(), mock_env_height(31), info, msg).unwrap();
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::RemoveFeeder { address: Addr::unchecked("feeder2") };
+        let res = execute(deps.as_mut(), mock_env_height(31), info, msg);
+        match res {
+            Err(ContractError::InvalidQuorum {}) => {}
+            _ => panic!("Must return error")
+        }
+    }
+
+    #[test]
+    fn placed_height() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        for feeder in ["feeder1", "feeder2", "feeder3"] {
+            let info = mock_info("creator", &vec![]);
+            let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked(feeder) };
+            let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+        }
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: Some(2), mode: None, block_entropy: None };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        // feeder3 commits after the bet placed at 5
+        for (feeder, committed_height) in [("feeder1", 3), ("feeder2", 3), ("feeder3", 5)] {
+            let info = mock_info(feeder, &vec![]);
+            let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(feeder.as_bytes())) };
+            let _res = execute(deps.as_mut(), mock_env_height(committed_height), info, msg).unwrap();
+        }
+        for feeder in ["feeder1", "feeder2", "feeder3"] {
+            let info = mock_info(feeder, &vec![]);
+            let msg = ExecuteMsg::RevealSeed { height: 10, seed: String::from(feeder) };
+            let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+        }
+        assert_eq!(Binary::from(sha_256(b"feeder1feeder2feeder3")).to_base64(), SEEDS.load(deps.as_ref().storage, 10).unwrap());
+
+        let verify = QueryMsg::VerifyOutcome { height: 10, entropy: None, min: 1, max: 100, placed_height: Some(5) };
+        let res = query(deps.as_ref(), mock_env_height(11), verify).unwrap();
+        let outcome: Option<VerifyOutcomeResponse> = from_binary(&res).unwrap();
+        let outcome = outcome.unwrap();
+        assert_eq!(Binary::from(sha_256(b"feeder1feeder2")).to_base64(), outcome.seed);
+        assert_eq!(vec![Binary::from(sha_256(b"feeder1")), Binary::from(sha_256(b"feeder2"))],
+            outcome.contributions.iter().map(|contribution| contribution.hash.clone()).collect::<Vec<Binary>>());
+
+        let res = query(deps.as_ref(), mock_env_height(11), QueryMsg::RandomBetween { height: 10, entropy: None, min_value: 1, max_value: 100, placed_height: Some(5) }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(Some(outcome.value), value);
+
+        // the contributions committed in time do not meet the quorum
+        let res = query(deps.as_ref(), mock_env_height(11), QueryMsg::RandomOne { height: 10, max_value: 99, entropy: None, max_lag: None, placed_height: Some(3) }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+    }
+
+    #[test]
+    fn drand_beacon() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        // drand mainnet
+        let pubkey = Binary::from(hex::decode("868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31").unwrap());
+        for (pubkey, genesis_time, period) in [
+            (pubkey.clone(), 1595431050, 0),
+            (Binary::from(&pubkey.as_slice()[1..]), 1595431050, 30),
+            (pubkey.clone(), 0, 30),
+            (pubkey.clone(), 1597614551, 30),
+        ] {
+            let info = mock_info("creator", &vec![]);
+            let msg = ExecuteMsg::UpdateDrand { pubkey, genesis_time, period };
+            let res = execute(deps.as_mut(), mock_env_time(0, 1597614550), info, msg);
+            match res {
+                Err(ContractError::InvalidDrand {}) => {}
+                _ => panic!("Must return error")
+            }
+        }
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateDrand { pubkey, genesis_time: 1595431050, period: 30 };
+        let _res = execute(deps.as_mut(), mock_env_time(0, 1597614550), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: None, mode: Some(RandomMode::Drand), block_entropy: None };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        // https://api.drand.sh/public/72785
+        let previous_signature = Binary::from(hex::decode("a609e19a03c2fcc559e8dae14900aaefe517cb55c840f6e69bc8e4f66c8d18e8a609685d9917efbfb0c37f058c2de88f13d297c7e19e0ab24813079efe57a182554ff054c7638153f9b26a60e7111f71a0ff63d9571704905d3ca6df0b031747").unwrap());
+        let signature = Binary::from(hex::decode("82f5d3d2de4db19d40a6980e8aa37842a0e55d1df06bd68bddc8d60002e8e959eb9cfa368b3c1b77d18f02a54fe047b80f0989315f83b12a74fd8679c4f12aae86eaf6ab5690b34f1fddd50ee3cc6f6cdf59e95526d5a5d82aaa84fa6f181e42").unwrap());
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::AddBeacon { round: 72786, previous_signature: previous_signature.clone(), signature: signature.clone() };
+        let res = execute(deps.as_mut(), mock_env_time(10, 1597614550), info, msg);
+        match res {
+            Err(ContractError::InvalidBeacon {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        // round 72784 is public at the block time, 72785 is the next one
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::AddBeacon { round: 72785, previous_signature, signature };
+        let _res = execute(deps.as_mut(), mock_env_time(10, 1597614550), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::Beacon { round: 72785 }).unwrap();
+        let randomness: Option<Binary> = from_binary(&res).unwrap();
+        assert_eq!(Some(Binary::from(hex::decode("8b676484b5fb1f37f9ec5c413d7d29883504e5b669f604a1ce68b3388e9ae3d9").unwrap())), randomness);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::DrandRound { height: 8 }).unwrap();
+        let round: Option<u64> = from_binary(&res).unwrap();
+        assert_eq!(Some(72785), round);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 8, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert!(value.is_some());
+
+        // no checkpoint after the height yet
+        let res = query(deps.as_ref(), mock_env_height(11), QueryMsg::RandomOne { height: 11, max_value: 99, entropy: None, max_lag: None, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+    }
+
+    #[test]
+    fn request_randomness() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let seed = String::from("0xSEED___________10___________");
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(seed.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("game", &vec![]);
+        let msg = ExecuteMsg::RequestRandomness {
+            request_id: 7,
+            resolve_height: 10,
+            entropy: None,
+            callback_msg: Binary::from(b"bet"),
+        };
+        let _res = execute(deps.as_mut(), mock_env_height(6), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(6), QueryMsg::PendingRequests { start_after: None, limit: None }).unwrap();
+        let requests: Vec<RequestResponse> = from_binary(&res).unwrap();
+        assert_eq!(vec![RequestResponse {
+            id: 1,
+            requester: Addr::unchecked("game"),
+            request_id: 7,
+            resolve_height: 10,
+            status: RequestStatus::Pending,
+        }], requests);
+
+        // nobody feeds height 8
+        for _ in 0..MAX_SCAN {
+            let info = mock_info("game", &vec![]);
+            let msg = ExecuteMsg::RequestRandomness {
+                request_id: 8,
+                resolve_height: 8,
+                entropy: None,
+                callback_msg: Binary::from(b"bet"),
+            };
+            let _res = execute(deps.as_mut(), mock_env_height(6), info, msg).unwrap();
+        }
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed };
+        let res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // the unseeded requests are skipped on the next dispatch
+        let info = mock_info("anyone", &vec![]);
+        let res = execute(deps.as_mut(), mock_env_height(10), info, ExecuteMsg::DispatchRequests {}).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!((8, MAX_SCAN as u64 + 1), DISPATCH_CURSOR.load(deps.as_ref().storage).unwrap());
+
+        // the seed arrives with a callback to the game
+        let info = mock_info("anyone", &vec![]);
+        let res = execute(deps.as_mut(), mock_env_height(10), info, ExecuteMsg::DispatchRequests {}).unwrap();
+        assert_eq!(1, res.messages.len());
+        assert_eq!(1, res.messages[0].id);
+        assert_eq!(cosmwasm_std::ReplyOn::Error, res.messages[0].reply_on);
+        assert_eq!(None, DISPATCH_CURSOR.may_load(deps.as_ref().storage).unwrap());
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::Request { id: 1 }).unwrap();
+        let request: RequestResponse = from_binary(&res).unwrap();
+        assert_eq!(RequestStatus::Dispatched, request.status);
+
+        // the callback failed
+        let msg = Reply { id: 1, result: cosmwasm_std::SubMsgResult::Err(String::from("error")) };
+        let _res = reply(deps.as_mut(), mock_env_height(10), msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::Request { id: 1 }).unwrap();
+        let request: RequestResponse = from_binary(&res).unwrap();
+        assert_eq!(RequestStatus::Failed, request.status);
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::RetryRequest { id: 1 };
+        let res = execute(deps.as_mut(), mock_env_height(11), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::RetryRequest { id: 1 };
+        let res = execute(deps.as_mut(), mock_env_height(11), info, msg);
+        match res {
+            Err(ContractError::InvalidStatus {}) => {}
+            _ => panic!("Must return error")
+        }
+    }
+
+    #[test]
+    fn draws() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let seed = String::from("0xSEED___________10___________");
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(seed.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomMany { height: 10, entropy: None, count: 50, min_value: 1, max_value: 6, placed_height: None }).unwrap();
+        let values: Option<Vec<u32>> = from_binary(&res).unwrap();
+        let values = values.unwrap();
+        assert_eq!(50, values.len());
+        assert!(values.iter().all(|value| (1..=6).contains(value)));
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::Shuffle { height: 10, entropy: None, n: 52, placed_height: None }).unwrap();
+        let cards: Option<Vec<u32>> = from_binary(&res).unwrap();
+        let mut cards = cards.unwrap();
+        assert_ne!((0..52).collect::<Vec<u32>>(), cards);
+        cards.sort_unstable();
+        assert_eq!((0..52).collect::<Vec<u32>>(), cards);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::WeightedPick { height: 10, entropy: None, weights: vec![0, 0, 5, 0], placed_height: None }).unwrap();
+        let index: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(Some(2), index);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::WeightedPick { height: 10, entropy: None, weights: vec![0, 0], placed_height: None });
+        assert!(res.is_err());
+
+        // every index below t, never t itself
+        let mut rng = Prng::new(b"seed", b"entropy");
+        let mut seen = [false; 3];
+        for _ in 0..100 {
+            seen[rng.select_one_of(3) as usize] = true;
+        }
+        assert_eq!([true; 3], seen);
+        assert_eq!(u32::MAX, Prng::new(b"seed", b"entropy").random_between(u32::MAX, u32::MAX));
+    }
+
+    #[test]
+    fn staking() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateStaking {
+            zerosum_token: Some(Addr::unchecked("zerosum")),
+            reward_contract: Some(Addr::unchecked("reward")),
+            collector_contract: Some(Addr::unchecked("collector")),
+            min_bond: Some(Uint128::from(1000u128)),
+            feed_reward: Some(Uint128::from(10u128)),
+            reveal_window: Some(5),
+            slash_rate: Some(Decimal::percent(10)),
+            feed_interval: Some(10),
+        };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        for feeder in ["feeder1", "feeder2"] {
+            let info = mock_info("zerosum", &vec![]);
+            let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: feeder.to_string(),
+                amount: Uint128::from(1000u128),
+                msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+            });
+            let _res = execute(deps.as_mut(), mock_env_height(1), info, msg).unwrap();
+        }
+        assert!(FEEDERS.has(deps.as_ref().storage, Addr::unchecked("feeder1")));
+
+        let seed = String::from("0xSEED___________10___________");
+        let info = mock_info("feeder1", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(seed.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("feeder2", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(b"withheld")) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        // the reward waits for the seed to be final
+        let info = mock_info("feeder1", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed };
+        let res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // bond is locked while a commitment is open
+        let info = mock_info("feeder2", &vec![]);
+        let msg = ExecuteMsg::Unbond { amount: None };
+        let res = execute(deps.as_mut(), mock_env_height(11), info, msg);
+        match res {
+            Err(ContractError::PendingCommitment {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::SlashMissed { height: 10, feeder: Addr::unchecked("feeder2") };
+        let res = execute(deps.as_mut(), mock_env_height(15), info, msg);
+        match res {
+            Err(ContractError::NotMissed {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        // the slash drops the withheld contribution and finalizes the seed, feeder1 is rewarded
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::SlashMissed { height: 10, feeder: Addr::unchecked("feeder2") };
+        let res = execute(deps.as_mut(), mock_env_height(16), info, msg).unwrap();
+        assert_eq!(2, res.messages.len());
+        assert_eq!(res.messages[1].msg, reward_msg(Addr::unchecked("reward"), Addr::unchecked("feeder1"), Uint128::from(10u128)).unwrap());
+        assert!(SEEDS.has(deps.as_ref().storage, 10));
+        assert!(!FEEDERS.has(deps.as_ref().storage, Addr::unchecked("feeder2")));
+
+        // feeder1 skipped the assigned height 20, its bond stays until the slash
+        let info = mock_info("feeder1", &vec![]);
+        let msg = ExecuteMsg::Unbond { amount: Some(Uint128::from(10u128)) };
+        let res = execute(deps.as_mut(), mock_env_height(26), info, msg);
+        match res {
+            Err(ContractError::PendingCommitment {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::SlashMissed { height: 20, feeder: Addr::unchecked("feeder1") };
+        let res = execute(deps.as_mut(), mock_env_height(31), info, msg);
+        match res {
+            Err(ContractError::NotMissed {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::SlashMissed { height: 20, feeder: Addr::unchecked("feeder1") };
+        let _res = execute(deps.as_mut(), mock_env_height(26), info, msg).unwrap();
+
+        // both fell below the minimum bond, feeder1 stays for the quorum
+        let res = query(deps.as_ref(), mock_env_height(26), QueryMsg::Feeders { start_after: None, limit: None }).unwrap();
+        let feeders: Vec<FeederResponse> = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("feeder1")], feeders.iter().map(|feeder| feeder.address.clone()).collect::<Vec<Addr>>());
+
+        let res = query(deps.as_ref(), mock_env_height(26), QueryMsg::Feeder { address: Addr::unchecked("feeder1") }).unwrap();
+        let feeder: FeederResponse = from_binary(&res).unwrap();
+        assert_eq!(FeederResponse {
+            address: Addr::unchecked("feeder1"),
+            bond: Uint128::from(900u128),
+            last_fed_height: 10,
+            missed: 1,
+            pending: 0,
+        }, feeder);
+
+        let res = query(deps.as_ref(), mock_env_height(26), QueryMsg::Feeder { address: Addr::unchecked("feeder2") }).unwrap();
+        let feeder: FeederResponse = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(900u128), feeder.bond);
+        assert_eq!(1, feeder.missed);
+
+        let info = mock_info("feeder2", &vec![]);
+        let msg = ExecuteMsg::Unbond { amount: None };
+        let res = execute(deps.as_mut(), mock_env_height(26), info, msg).unwrap();
+        assert_eq!(1, res.messages.len());
+    }
+
+    #[test]
+    fn seed_history() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let seed10 = String::from("0xSEED___________10___________");
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(seed10.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: seed10 };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::SeedAt { height: 8 }).unwrap();
+        let seed: Option<String> = from_binary(&res).unwrap();
+        assert_eq!(None, seed);
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::SeedAt { height: 10 }).unwrap();
+        let seed: Option<String> = from_binary(&res).unwrap();
+        assert!(seed.is_some());
+
+        // height 8 has no round of its own, the one of height 10 is not taken for it
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 8, entropy: None, max_value: 99, max_lag: Some(2), placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(None, value);
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomOne { height: 10, entropy: None, max_value: 99, max_lag: Some(0), placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert!(value.is_some());
+
+        let seed20 = String::from("0xSEED___________20___________");
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 20, hash: Binary::from(sha_256(seed20.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(12), info, msg).unwrap();
+
+        let info = mock_info("game", &vec![]);
+        let msg = ExecuteMsg::RequestRandomness {
+            request_id: 1,
+            resolve_height: 20,
+            entropy: None,
+            callback_msg: Binary::from(b"bet"),
+        };
+        let _res = execute(deps.as_mut(), mock_env_height(13), info, msg).unwrap();
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::PruneSeeds { before_height: 30 };
+        let res = execute(deps.as_mut(), mock_env_height(16), info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        // the bets on 10 may still be open
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::PruneSeeds { before_height: 30 };
+        let res = execute(deps.as_mut(), mock_env_height(16), info, msg).unwrap();
+        assert_eq!(vec![("method", "prune_seeds"), ("prune_height", "0"), ("pruned", "0")],
+            res.attributes.iter().map(|attr| (attr.key.as_str(), attr.value.as_str())).collect::<Vec<(&str, &str)>>());
+
+        // the request waiting for 20 keeps everything from there on
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::PruneSeeds { before_height: 30 };
+        let res = execute(deps.as_mut(), mock_env_height(MIN_SEED_AGE + 30), info, msg).unwrap();
+        assert_eq!(vec![("method", "prune_seeds"), ("prune_height", "20"), ("pruned", "1")],
+            res.attributes.iter().map(|attr| (attr.key.as_str(), attr.value.as_str())).collect::<Vec<(&str, &str)>>());
+        assert_eq!(20, PRUNED_BEFORE.load(deps.as_ref().storage).unwrap());
+
+        let height = MIN_SEED_AGE + 30;
+        let res = query(deps.as_ref(), mock_env_height(height), QueryMsg::SeedAt { height: 10 }).unwrap();
+        let seed: Option<String> = from_binary(&res).unwrap();
+        assert_eq!(None, seed);
+        let res = query(deps.as_ref(), mock_env_height(height), QueryMsg::Commitment { height: 20 }).unwrap();
+        let commitment: CommitmentResponse = from_binary(&res).unwrap();
+        assert_eq!(vec![Addr::unchecked("feeder")], commitment.committed);
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 20, seed: seed20 };
+        let _res = execute(deps.as_mut(), mock_env_height(height), info, msg).unwrap();
+        let info = mock_info("anyone", &vec![]);
+        let res = execute(deps.as_mut(), mock_env_height(height), info, ExecuteMsg::DispatchRequests {}).unwrap();
+        assert_eq!(1, res.messages.len());
+
+        let res = query(deps.as_ref(), mock_env_height(height), QueryMsg::Seeds { start_after: None, limit: None }).unwrap();
+        let seeds: Vec<SeedResponse> = from_binary(&res).unwrap();
+        assert_eq!(vec![20], seeds.iter().map(|seed| seed.height).collect::<Vec<u64>>());
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::PruneSeeds { before_height: 30 };
+        let _res = execute(deps.as_mut(), mock_env_height(height), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(height), QueryMsg::Seeds { start_after: None, limit: None }).unwrap();
+        let seeds: Vec<SeedResponse> = from_binary(&res).unwrap();
+        assert!(seeds.is_empty());
+        let res = query(deps.as_ref(), mock_env_height(height), QueryMsg::Commitment { height: 20 }).unwrap();
+        let commitment: CommitmentResponse = from_binary(&res).unwrap();
+        assert!(commitment.committed.is_empty());
+    }
+
+    #[test]
+    fn verify_outcome() {
+        let mut deps = mock_dependencies();
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let verify = QueryMsg::VerifyOutcome { height: 10, entropy: Some(vec![1, 2, 3]), min: 1, max: 100, placed_height: None };
+        let res = query(deps.as_ref(), mock_env_height(5), verify.clone()).unwrap();
+        let outcome: Option<VerifyOutcomeResponse> = from_binary(&res).unwrap();
+        assert_eq!(None, outcome);
+
+        let seed = String::from("0xSEED___________10___________");
+        let hash = Binary::from(sha_256(seed.as_bytes()));
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: hash.clone() };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed: seed.clone() };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env_height(10), verify).unwrap();
+        let outcome: Option<VerifyOutcomeResponse> = from_binary(&res).unwrap();
+        let outcome = outcome.unwrap();
+        assert_eq!(Some(10), outcome.seed_height);
+        assert_eq!(vec![ContributionResponse { feeder: Addr::unchecked("feeder"), hash, seed: seed.clone() }], outcome.contributions);
+        assert_eq!(None, outcome.drand_round);
+        assert_eq!(Binary::from(sha_256(seed.as_bytes())).to_base64(), outcome.seed);
+        assert_eq!(vec![0, 0, 0, 0, 0, 0, 0, 10, 1, 2, 3], outcome.entropy.to_vec());
+
+        let res = query(deps.as_ref(), mock_env_height(10), QueryMsg::RandomBetween { height: 10, entropy: Some(vec![1, 2, 3]), min_value: 1, max_value: 100, placed_height: None }).unwrap();
+        let value: Option<u32> = from_binary(&res).unwrap();
+        assert_eq!(Some(outcome.value), value);
+
+        // the published inputs are enough to recompute the outcome
+        let mut rng = Prng::new(outcome.seed.as_bytes(), outcome.entropy.as_slice());
+        assert_eq!(outcome.chacha_seed.to_vec(), rng.chacha_seed().to_vec());
+        assert_eq!(outcome.value, rng.random_between(1, 100));
+    }
+
+    #[test]
+    fn block_entropy() {
+        let mut deps = mock_dependencies_with_blocks(&[(10, "BLOCK10")]);
+
+        let msg = InstantiateMsg {};
+        let info = mock_info("creator", &vec![]);
+        let _res = instantiate(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::AddFeeder { address: Addr::unchecked("feeder") };
+        let _res = execute(deps.as_mut(), mock_env_height(0), info, msg).unwrap();
+
+        let seed = String::from("0xSEED___________10___________");
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::CommitSeed { height: 10, hash: Binary::from(sha_256(seed.as_bytes())) };
+        let _res = execute(deps.as_mut(), mock_env_height(5), info, msg).unwrap();
+
+        let info = mock_info("feeder", &vec![]);
+        let msg = ExecuteMsg::RevealSeed { height: 10, seed };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        let draws = QueryMsg::RandomMany { height: 10, entropy: None, count: 20, min_value: 0, max_value: 1000, placed_height: None };
+        let res = query(deps.as_ref(), mock_env_height(10), draws.clone()).unwrap();
+        let plain: Option<Vec<u32>> = from_binary(&res).unwrap();
+        assert!(plain.is_some());
+
+        let info = mock_info("anyone", &vec![]);
+        let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: None, mode: None, block_entropy: Some(true) };
+        let res = execute(deps.as_mut(), mock_env_height(10), info, msg);
+        match res {
+            Err(ContractError::Unauthorized {}) => {}
+            _ => panic!("Must return error")
+        }
+
+        let info = mock_info("creator", &vec![]);
+        let msg = ExecuteMsg::UpdateState { gov_contract: None, quorum: None, mode: None, block_entropy: Some(true) };
+        let _res = execute(deps.as_mut(), mock_env_height(10), info, msg).unwrap();
+
+        // the hash of the current block is not known yet
+        let res = query(deps.as_ref(), mock_env_height(10), draws.clone()).unwrap();
+        let mixed: Option<Vec<u32>> = from_binary(&res).unwrap();
+        assert_eq!(None, mixed);
+
+        let res = query(deps.as_ref(), mock_env_height(11), draws.clone()).unwrap();
+        let mixed: Option<Vec<u32>> = from_binary(&res).unwrap();
+        assert!(mixed.is_some());
+        assert_ne!(plain, mixed);
+
+        // the same seed on another block gives other draws
+        deps.querier.hashes.insert(10, String::from("OTHER10"));
+        let res = query(deps.as_ref(), mock_env_height(11), draws.clone()).unwrap();
+        let other: Option<Vec<u32>> = from_binary(&res).unwrap();
+        assert!(other.is_some());
+        assert_ne!(mixed, other);
+
+        // a block the chain can not return fails the query
+        deps.querier.hashes.remove(&10);
+        let res = query(deps.as_ref(), mock_env_height(11), QueryMsg::RandomOne { height: 10, entropy: None, max_value: 10, max_lag: None, placed_height: None });
+        assert!(res.is_err());
+    }
+
+    // answers the tendermint block query with the given hashes, everything else goes to the mock querier
+    pub struct BlockQuerier {
+        base: MockQuerier,
+        pub hashes: HashMap<u64, String>,
+    }
+
+    impl Querier for BlockQuerier {
+        fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+            match from_slice::<QueryRequest<TendermintQueryWrapper>>(bin_request) {
+                Ok(QueryRequest::Custom(TendermintQueryWrapper { query_data: TendermintQuery::BlockInfo { height }, .. })) => {
+                    match self.hashes.get(&height) {
+                        Some(hash) => {
+                            let res = BlockInfoResposne { block_id: Block { hash: hash.clone() } };
+                            SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+                        },
+                        None => SystemResult::Err(SystemError::InvalidRequest {
+                            error: format!("block {} not found", height),
+                            request: bin_request.into(),
+                        }),
+                    }
+                },
+                _ => self.base.raw_query(bin_request),
+            }
+        }
+    }
+
+    pub fn mock_dependencies_with_blocks(hashes: &[(u64, &str)]) -> OwnedDeps<MockStorage, MockApi, BlockQuerier> {
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier: BlockQuerier {
+                base: MockQuerier::new(&[]),
+                hashes: hashes.iter().map(|(height, hash)| (*height, hash.to_string())).collect(),
+            },
+            custom_query_type: PhantomData,
+        }
+    }
+
+    pub fn mock_env_time(height: u64, seconds: u64) -> Env {
+        let mut env = mock_env_height(height);
+        env.block.time = Timestamp::from_seconds(seconds);
+        env
+    }
+
     pub fn mock_env_height(height: u64) -> Env {
         Env {
             block: BlockInfo {
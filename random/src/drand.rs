@@ -0,0 +1,57 @@
+use std::convert::TryInto;
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{pairing, G1Affine, G2Affine, G2Projective};
+use sha2::{Digest, Sha256};
+
+use crate::rand::sha_256;
+
+// domain separation tag of the drand mainnet chained scheme
+const DOMAIN: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Checks the group public key is a compressed G1 point
+pub fn valid_pubkey(pubkey: &[u8]) -> bool {
+    let pubkey: [u8; 48] = match pubkey.try_into() {
+        Ok(pubkey) => pubkey,
+        Err(_) => return false,
+    };
+    G1Affine::from_compressed(&pubkey).is_some().into()
+}
+
+/// Verifies a chained drand beacon against the group public key (G1, compressed)
+pub fn verify(pubkey: &[u8], round: u64, previous_signature: &[u8], signature: &[u8]) -> bool {
+    let pubkey: [u8; 48] = match pubkey.try_into() {
+        Ok(pubkey) => pubkey,
+        Err(_) => return false,
+    };
+    let signature: [u8; 96] = match signature.try_into() {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let pubkey = G1Affine::from_compressed(&pubkey);
+    let signature = G2Affine::from_compressed(&signature);
+    if pubkey.is_none().into() || signature.is_none().into() {
+        return false;
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(previous_signature);
+    hasher.update(round.to_be_bytes());
+    let message = hasher.finalize();
+    let message_on_curve = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(message.as_slice(), DOMAIN);
+
+    pairing(&G1Affine::generator(), &signature.unwrap()) == pairing(&pubkey.unwrap(), &G2Affine::from(message_on_curve))
+}
+
+/// Randomness of a beacon, sha256 of the signature
+pub fn randomness(signature: &[u8]) -> [u8; 32] {
+    sha_256(signature)
+}
+
+/// First round published strictly after `time` (seconds)
+pub fn round_after(genesis_time: u64, period: u64, time: u64) -> u64 {
+    if time < genesis_time {
+        return 1;
+    }
+    (time - genesis_time) / period + 2
+}
@@ -14,6 +14,39 @@ pub enum ContractError {
 
     #[error("Not Feeder")]
     NotFeeder {},
+
+    #[error("Not Exist")]
+    NotExist {},
+
+    #[error("Invalid Height")]
+    InvalidHeight {},
+
+    #[error("Seed Mismatch")]
+    SeedMismatch {},
+
+    #[error("Invalid Beacon")]
+    InvalidBeacon {},
+
+    #[error("Invalid Status")]
+    InvalidStatus {},
+
+    #[error("Not Fed Seed")]
+    NotFed {},
+
+    #[error("Insufficient")]
+    Insufficient {},
+
+    #[error("Pending Commitment")]
+    PendingCommitment {},
+
+    #[error("Not Missed")]
+    NotMissed {},
+
+    #[error("Invalid Quorum")]
+    InvalidQuorum {},
+
+    #[error("Invalid Drand")]
+    InvalidDrand {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -2,5 +2,6 @@ pub mod contract;
 mod error;
 pub mod state;
 pub mod rand;
+pub mod drand;
 
 pub use crate::error::ContractError;
@@ -13,6 +13,18 @@ pub fn sha_256(data: &[u8]) -> [u8; 32] {
     result
 }
 
+/// Entropy of the draws on a height, the height followed by the entropy of the caller
+pub fn derive_entropy(height: u64, entropy: Option<Vec<u8>>) -> Vec<u8> {
+    let mut entropy_vec = height.to_be_bytes().to_vec();
+    entropy_vec.extend(entropy.unwrap_or_default());
+    entropy_vec
+}
+
+/// The generator the contract uses for the draws on a height
+pub fn derive_prng(seed: &str, height: u64, entropy: Option<Vec<u8>>) -> Prng {
+    Prng::new(seed.as_bytes(), derive_entropy(height, entropy).as_slice())
+}
+
 pub struct Prng {
     seed: Vec<u8>,
     entropy: Vec<u8>,
@@ -34,51 +46,116 @@ impl Prng {
             return 0;
         }
 
-        return from + (self.rand_u32() % (to - from + 1));
+        from + self.random_below_u64((to - from) as u64 + 1) as u32
     }
 
-    /// Return an item from an iterable structure
-    pub fn select_one_of(&mut self, t: u32) -> u32 {
-        let rand = self.random_between(0, t);
+    /// Return a random u64 (inclusive) between `from` and `to`
+    pub fn random_between_u64(&mut self, from: u64, to: u64) -> u64 {
+        if from > to {
+            return 0;
+        }
 
-        return rand;
+        from + self.random_below_u128((to - from) as u128 + 1) as u64
     }
 
-    pub fn rand_u32(&mut self) -> u32 {
-        let mut hasher = Sha256::new();
+    /// Return an index below `t` of an iterable structure
+    pub fn select_one_of(&mut self, t: u32) -> u32 {
+        if t == 0 {
+            return 0;
+        }
 
-        // write input message
-        hasher.update(&self.seed);
-        hasher.update(&self.entropy);
-        let hash = hasher.finalize();
+        self.random_between(0, t - 1)
+    }
 
-        let mut result = [0u8; 32];
-        result.copy_from_slice(hash.as_slice());
+    /// Return the numbers below `n` in random order (Fisher-Yates)
+    pub fn shuffle(&mut self, n: u32) -> Vec<u32> {
+        let mut items: Vec<u32> = (0..n).collect();
+        for i in (1..items.len()).rev() {
+            let j = self.random_between(0, i as u32) as usize;
+            items.swap(i, j);
+        }
+        items
+    }
 
-        let mut rng: ChaChaRng = ChaChaRng::from_seed(result);
+    /// Return an index of `weights` picked with a chance proportional to its weight
+    pub fn weighted_pick(&mut self, weights: &[u32]) -> Option<u32> {
+        let total: u64 = weights.iter().map(|weight| *weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
 
-        rng.set_word_pos(self.pos.into());
-        self.pos += 8;
+        let mut point = self.random_between_u64(0, total - 1);
+        for (index, weight) in weights.iter().enumerate() {
+            if point < *weight as u64 {
+                return Some(index as u32);
+            }
+            point -= *weight as u64;
+        }
+        None
+    }
 
-        let mut output = [0u32; 8];
-        for i in output.iter_mut() {
-            *i = rng.next_u32();
+    // draws below `range` (at most 2^32) rejecting the biased tail of the u32 space
+    fn random_below_u64(&mut self, range: u64) -> u64 {
+        let space: u64 = 1 << 32;
+        let limit = space - space % range;
+        loop {
+            let value = self.rand_u32() as u64;
+            if value < limit {
+                return value % range;
+            }
         }
+    }
 
-        output[0]
+    // draws below `range` (at most 2^64) rejecting the biased tail of the u64 space
+    fn random_below_u128(&mut self, range: u128) -> u128 {
+        let space: u128 = 1 << 64;
+        let limit = space - space % range;
+        loop {
+            let value = self.rand_u64() as u128;
+            if value < limit {
+                return value % range;
+            }
+        }
     }
 
-    pub fn rand_bytes(&mut self, ) -> [u8; 32] {
+    pub fn rand_u32(&mut self) -> u32 {
+        self.next_rng().next_u32()
+    }
+
+    pub fn rand_u64(&mut self) -> u64 {
+        self.next_rng().next_u64()
+    }
+
+    pub fn rand_u128(&mut self) -> u128 {
+        let mut rng = self.next_rng();
+        ((rng.next_u64() as u128) << 64) | rng.next_u64() as u128
+    }
+
+    // stream of the seed and entropy at the next position
+    fn next_rng(&mut self) -> ChaChaRng {
+        let mut rng: ChaChaRng = ChaChaRng::from_seed(self.chacha_seed());
+
+        rng.set_word_pos(self.pos);
+        self.pos += 8;
+        rng
+    }
+
+    /// Return the key of the ChaCha stream, sha256 of the seed and entropy
+    pub fn chacha_seed(&self) -> [u8; 32] {
         let mut hasher = Sha256::new();
-        
+
+        // write input message
         hasher.update(&self.seed);
         hasher.update(&self.entropy);
         let hash = hasher.finalize();
 
         let mut result = [0u8; 32];
         result.copy_from_slice(hash.as_slice());
-        
-        let mut rng: ChaChaRng = ChaChaRng::from_seed(result);
+        result
+    }
+
+    pub fn rand_bytes(&mut self, ) -> [u8; 32] {
+        let mut rng: ChaChaRng = ChaChaRng::from_seed(self.chacha_seed());
         let mut bytes = [0u8; 32];
         rng.fill_bytes(&mut bytes);
 
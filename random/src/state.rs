@@ -1,14 +1,113 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary, Decimal, StdResult, Storage, Uint128};
+use zerosum::random::{RandomMode, RequestStatus};
 use cw_storage_plus::{Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub gov_contract: Addr,
+    // contributions a seed needs
+    pub quorum: u32,
+    pub mode: RandomMode,
+    // mix the hash of the target block into the seed
+    #[serde(default)]
+    pub block_entropy: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RandomnessRequest {
+    pub requester: Addr,
+    pub request_id: u64,
+    pub resolve_height: u64,
+    pub entropy: Option<Vec<u8>>,
+    pub callback_msg: Binary,
+    pub status: RequestStatus,
+    // height the request was made at, None for the requests made before it was kept
+    #[serde(default)]
+    pub placed_height: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DrandConfig {
+    pub pubkey: Binary,
+    pub genesis_time: u64,
+    pub period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingConfig {
+    pub zerosum_token: Addr,
+    pub reward_contract: Addr,
+    pub collector_contract: Addr,
+    // bond a feeder needs to register itself
+    pub min_bond: Uint128,
+    // paid through the reward contract per revealed contribution
+    pub feed_reward: Uint128,
+    // blocks after the height a commitment has to be revealed in
+    pub reveal_window: u64,
+    pub slash_rate: Decimal,
+    // every feeder is expected to contribute to the heights divisible by it, 0 for none
+    pub feed_interval: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct FeederInfo {
+    pub bond: Uint128,
+    pub bonded_height: u64,
+    pub last_fed_height: u64,
+    pub missed: u64,
+    pub pending: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Commitment {
+    pub hash: Binary,
+    pub committed_height: u64,
+    pub seed: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SeedRound {
+    pub commitments: u32,
+    pub reveals: u32,
+    pub last_committed_height: u64,
 }
 
 pub const FEEDERS: Map<Addr, bool> = Map::new("feeders");
+pub const FEEDER_INFOS: Map<Addr, FeederInfo> = Map::new("feeder_infos");
+pub const STAKING: Item<StakingConfig> = Item::new("staking");
+// height, feeder already slashed for the height
+pub const SLASHED: Map<(u64, Addr), bool> = Map::new("slashed");
 pub const SEEDS: Map<u64, String> = Map::new("seeds");
 pub const STATE: Item<State> = Item::new("state");
+// height, feeder, contribution to the seed of the height
+pub const COMMITMENTS: Map<(u64, Addr), Commitment> = Map::new("commitments");
+// height, feeder whose unrevealed commitment was dropped when the seed was finalized, until it is slashed
+pub const WITHHELD: Map<(u64, Addr), bool> = Map::new("withheld");
+pub const SEED_ROUNDS: Map<u64, SeedRound> = Map::new("seed_rounds");
+
+pub const DRAND: Item<DrandConfig> = Item::new("drand");
+// round, randomness
+pub const BEACONS: Map<u64, Binary> = Map::new("beacons");
+// height, first round published after the block time of the height
+pub const CHECKPOINTS: Map<u64, u64> = Map::new("checkpoints");
+
+pub fn next_request_index(store: &mut dyn Storage) -> StdResult<u64> {
+    let index = REQUEST_INDEX.may_load(store)?.unwrap_or_default() + 1;
+    REQUEST_INDEX.save(store, &index)?;
+    Ok(index)
+}
+
+pub const REQUEST_INDEX: Item<u64> = Item::new("request_index");
+// id, request. the id is also the reply id of its callback
+pub const REQUESTS: Map<u64, RandomnessRequest> = Map::new("requests");
+// resolve height, id of the requests waiting for a seed
+pub const REQUEST_QUEUE: Map<(u64, u64), bool> = Map::new("request_queue");
+// resolve height, id of the requests whose callback failed, they keep their seed until retried
+pub const FAILED_REQUESTS: Map<(u64, u64), bool> = Map::new("failed_requests");
+// heights below it can be pruned, their commitments are no record of the reveals
+pub const PRUNED_BEFORE: Item<u64> = Item::new("pruned_before");
+// last queue key looked at by the dispatch, it goes on after it
+pub const DISPATCH_CURSOR: Item<(u64, u64)> = Item::new("dispatch_cursor");
@@ -1,23 +1,31 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Addr, Uint128, Order, Decimal, CosmosMsg, WasmMsg};
+    StdResult, Addr, Uint128, Order, Decimal, CosmosMsg, WasmMsg, Storage};
 use cw2::set_contract_version;
 use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg};
 use cw_storage_plus::{Bound};
 
-use zerosum::referral::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, UserAmountInfo, RoundShareInfo, RoundShareInfos, UserShareInfo};
+use zerosum::referral::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, UserAmountInfo, RoundShareInfo, RoundShareInfos, UserShareInfo,
+    ReferralCodeResponse, Tier, TiersResponse, ReferrerTierResponse, LeaderboardKind, ReferralInfo, FollowerInfo, DownlineResponse,
+    FollowingHistory, ClaimEstimateResponse, ClaimWindow, ExpiringResponse, ExpiringRound,
+    Qualification, QualifiedResponse};
 use zerosum::round::{get_round};
-use zerosum::asset::{token_asset, token_asset_info};
+use zerosum::asset::{token_asset, token_asset_info, Asset, AssetInfo};
 use zerosum::reward::{reward_msg};
+use zerosum::claim::{claim_accounts, set_claim_delegate};
 
 use crate::error::ContractError;
-use crate::state::{State, STATE, RoundInfo, Referral, REFERRALS, REWARD_SHARE, TOTAL_REWARD_SHARE, REFERRAL_HISTORY, 
-    REWARDS, ROUNDS, LAST_CLAIM_ROUND, FOLLOWING, FOLLOWERS, FOLLOWER_IDX, Reward};
+use crate::state::{State, STATE, RoundInfo, Referral, REFERRALS, KICKBACKS, REFERRAL_CODES, REWARD_SHARE, TOTAL_REWARD_SHARE, REFERRAL_HISTORY, KICKBACK_HISTORY,
+    REWARDS, ROUNDS, LAST_CLAIM_ROUND, FOLLOWING, FOLLOWERS, FOLLOWER_IDX, Reward,
+    TierConfig, TIERS, VOLUME_BOARD, REWARD_BOARD, ALL_TIME, DOWNLINE, FOLLOWER_KEY, FOLLOWER_ADDRS, FOLLOWING_SINCE, FOLLOWING_HISTORY, SWEEP_ROUND,
+    QUALIFICATION, ACTIVE_FOLLOWERS, CLAIM_DELEGATES, DEREGISTERED};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:referral";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// 탈퇴할 때 한 번에 옮기는 팔로워 수
+const DEFAULT_MIGRATE_LIMIT: u64 = 20;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -35,6 +43,12 @@ pub fn instantiate(
         referral_ratio: msg.referral_ratio.unwrap_or_default(),
         collector_contract: msg.collector_contract.unwrap_or(Addr::unchecked("")),
         reward_contract: msg.reward_contract.unwrap_or(Addr::unchecked("")),
+        rename_code_fee: msg.rename_code_fee.unwrap_or_default(),
+        following_cooldown: msg.following_cooldown.unwrap_or_default(),
+        reward_expiry: msg.reward_expiry.unwrap_or_default(),
+        deregister_tenure: msg.deregister_tenure.unwrap_or_default(),
+        deregister_refund_ratio: msg.deregister_refund_ratio.unwrap_or_default(),
+        total_fees: Uint128::zero(),
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
@@ -62,6 +76,11 @@ pub fn execute(
             referral_ratio,
             collector_contract,
             reward_contract,
+            rename_code_fee,
+            following_cooldown,
+            reward_expiry,
+            deregister_tenure,
+            deregister_refund_ratio,
         } => execute_update_state(
             deps,
             info,
@@ -73,22 +92,52 @@ pub fn execute(
             referral_ratio,
             collector_contract,
             reward_contract,
+            rename_code_fee,
+            following_cooldown,
+            reward_expiry,
+            deregister_tenure,
+            deregister_refund_ratio,
         ),
-        ExecuteMsg::AddFollowing { address } => execute_add_following(deps, info, address), 
+        ExecuteMsg::AddFollowing { address } => execute_add_following(deps, env, info, address), 
+        ExecuteMsg::ChangeFollowing { address } => execute_change_following(deps, env, info, address),
+        ExecuteMsg::AddFollowingByCode { code } => execute_add_following_by_code(deps, env, info, code),
+        ExecuteMsg::UpdateTiers { window, tiers } => execute_update_tiers(deps, info, window, tiers),
+        ExecuteMsg::SetKickback { ratio } => execute_set_kickback(deps, info, ratio),
+        ExecuteMsg::UpdateQualification { qualification } => execute_update_qualification(deps, info, qualification),
+        ExecuteMsg::Deregister {} => execute_deregister(deps, env, info),
+        ExecuteMsg::MigrateFollowers { referrer, limit } => execute_migrate_followers(deps, referrer, limit),
         ExecuteMsg::AddShare { address, amount } => execute_add_share(deps, env, info, address, amount),
-        ExecuteMsg::Claim { start_round } => execute_claim(deps, env, info, start_round),
-        ExecuteMsg::ClaimReferral {} => execute_claim_referral(deps, info),
+        ExecuteMsg::Claim { start_round, max_rounds, owner, recipient } => {
+            let (owner, recipient) = claim_accounts(deps.storage, deps.api, &CLAIM_DELEGATES, &info.sender, owner, recipient)?
+                .ok_or(ContractError::Unauthorized {})?;
+            execute_claim(deps, env, owner, recipient, start_round, max_rounds)
+        },
+        ExecuteMsg::ClaimReferral { owner, recipient } => {
+            let (owner, recipient) = claim_accounts(deps.storage, deps.api, &CLAIM_DELEGATES, &info.sender, owner, recipient)?
+                .ok_or(ContractError::Unauthorized {})?;
+            execute_claim_referral(deps, env, owner, recipient)
+        },
+        ExecuteMsg::SetClaimDelegate { delegate } => execute_set_claim_delegate(deps, info, delegate),
+        ExecuteMsg::SweepExpired { referrers, limit } => execute_sweep_expired(deps, env, referrers, limit),
+        ExecuteMsg::AddEdge { address } => {
+            let coin = info.funds[0].clone();
+            execute_add_edge(deps, env, info.sender, address, Asset::from(coin))
+        },
         ExecuteMsg::Collect {} => execute_collect(deps, env),
     }
 }
 
-fn receive_cw20(deps: DepsMut, _env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
+fn receive_cw20(deps: DepsMut, env: Env, info: MessageInfo, cw20_msg: Cw20ReceiveMsg) -> Result<Response, ContractError> {
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::RegisterReferral { addr, name, description }) => {
-            execute_register_referral(deps, info, addr, name, description, Addr::unchecked(cw20_msg.sender), cw20_msg.amount)
+        Ok(Cw20HookMsg::RegisterReferral { addr, name, description, code }) => {
+            execute_register_referral(deps, env, info, addr, name, description, code, Addr::unchecked(cw20_msg.sender), cw20_msg.amount)
         },
+        Ok(Cw20HookMsg::RenameCode { code }) =>
+            execute_rename_code(deps, info, code, Addr::unchecked(cw20_msg.sender), cw20_msg.amount),
         Ok(Cw20HookMsg::AddRound { round }) =>
             execute_add_round(deps, info, round, Addr::unchecked(cw20_msg.sender), cw20_msg.amount),
+        Ok(Cw20HookMsg::AddEdge { address }) =>
+            execute_add_edge(deps, env, Addr::unchecked(cw20_msg.sender), address, token_asset(info.sender, cw20_msg.amount)),
         Err(err) => Err(ContractError::Std(err)),
     }
 }
@@ -104,6 +153,11 @@ pub fn execute_update_state(
     referral_ratio: Option<Vec<Decimal>>,
     collector_contract: Option<Addr>,
     reward_contract: Option<Addr>,
+    rename_code_fee: Option<Uint128>,
+    following_cooldown: Option<u64>,
+    reward_expiry: Option<u64>,
+    deregister_tenure: Option<u64>,
+    deregister_refund_ratio: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
         if state.gov_contract != info.sender {
@@ -133,14 +187,81 @@ pub fn execute_update_state(
         if reward_contract.is_some() {
             state.reward_contract = reward_contract.unwrap();
         }
+        if rename_code_fee.is_some() {
+            state.rename_code_fee = rename_code_fee.unwrap();
+        }
+        if following_cooldown.is_some() {
+            state.following_cooldown = following_cooldown.unwrap();
+        }
+        if reward_expiry.is_some() {
+            state.reward_expiry = reward_expiry.unwrap();
+        }
+        if deregister_tenure.is_some() {
+            state.deregister_tenure = deregister_tenure.unwrap();
+        }
+        if deregister_refund_ratio.is_some() {
+            if deregister_refund_ratio.unwrap() > Decimal::one() {
+                return Err(ContractError::InvalidRatio {});
+            }
+            state.deregister_refund_ratio = deregister_refund_ratio.unwrap();
+        }
         Ok(state)
     })?;
 
     Ok(Response::new().add_attribute("method", "try_increment"))
 }
 
-pub fn execute_add_following(deps: DepsMut, info: MessageInfo, address: Addr) -> Result<Response, ContractError> {
-    if address == info.sender {
+pub fn execute_add_following(deps: DepsMut, env: Env, info: MessageInfo, address: Addr) -> Result<Response, ContractError> {
+    check_following(deps.as_ref(), &info.sender, &address)?;
+
+    FOLLOWING.update(deps.storage, info.sender.clone(), |prev| {
+        match prev {
+            Some(_) => Err(ContractError::AlreadyExist {}),
+            None => Ok(address.clone()),
+        }
+    })?;
+    add_follower(deps.storage, &address, &info.sender)?;
+    FOLLOWING_SINCE.save(deps.storage, info.sender.clone(), &get_round(env.block.height).unwrap_or_default())?;
+    update_downline(deps.storage, &info.sender, true)?;
+    Ok(Response::new()
+        .add_attribute("method", "add_following"))
+}
+
+pub fn execute_change_following(deps: DepsMut, env: Env, info: MessageInfo, address: Addr) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    let prev_address = FOLLOWING.may_load(deps.storage, info.sender.clone())?.ok_or(ContractError::NotExist {})?;
+    if prev_address == address {
+        return Err(ContractError::AlreadyExist {});
+    }
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let since = FOLLOWING_SINCE.may_load(deps.storage, info.sender.clone())?.unwrap_or_default();
+    if current_round < since + state.following_cooldown {
+        return Err(ContractError::Cooldown {});
+    }
+    check_following(deps.as_ref(), &info.sender, &address)?;
+
+    update_downline(deps.storage, &info.sender, false)?;
+    remove_follower(deps.storage, &prev_address, &info.sender)?;
+    // 이전 라운드의 리워드는 이전 팔로잉에게
+    let history_idx = FOLLOWING_HISTORY.prefix(info.sender.clone()).keys(deps.storage, None, None, Order::Ascending).count() as u64;
+    FOLLOWING_HISTORY.save(deps.storage, (info.sender.clone(), history_idx), &FollowingHistory {
+        referrer: prev_address.clone(),
+        to_round: current_round,
+    })?;
+
+    FOLLOWING.save(deps.storage, info.sender.clone(), &address)?;
+    add_follower(deps.storage, &address, &info.sender)?;
+    FOLLOWING_SINCE.save(deps.storage, info.sender.clone(), &current_round)?;
+    update_downline(deps.storage, &info.sender, true)?;
+    Ok(Response::new()
+        .add_attribute("method", "change_following")
+        .add_attribute("prev_address", prev_address)
+        .add_attribute("address", address))
+}
+
+// the address must be a referrer and following it can not make a cycle
+fn check_following(deps: Deps, sender: &Addr, address: &Addr) -> Result<(), ContractError> {
+    if address == sender {
         return Err(ContractError::Unauthorized {});
     }
     let mut next_address = address.clone();
@@ -148,7 +269,7 @@ pub fn execute_add_following(deps: DepsMut, info: MessageInfo, address: Addr) ->
         let result = FOLLOWING.may_load(deps.storage, next_address)?;
         if result.is_some() {
             next_address = result.unwrap();
-            if next_address == info.sender {
+            if next_address == *sender {
                 return Err(ContractError::Unauthorized {});
             }
         } else {
@@ -158,43 +279,284 @@ pub fn execute_add_following(deps: DepsMut, info: MessageInfo, address: Addr) ->
     if !REFERRALS.has(deps.storage, address.clone()) {
         return Err(ContractError::NotReferral {});
     }
+    Ok(())
+}
 
-    FOLLOWING.update(deps.storage, info.sender.clone(), |prev| {
-        match prev {
-            Some(_) => Err(ContractError::AlreadyExist {}),
-            None => Ok(address.clone()),
-        }
-    })?;
-    let follower_idx = FOLLOWER_IDX.update(deps.storage, address.clone(), |prev| -> Result<u64, ContractError> {
+fn add_follower(storage: &mut dyn Storage, address: &Addr, follower: &Addr) -> StdResult<()> {
+    let follower_idx = FOLLOWER_IDX.update(storage, address.clone(), |prev| -> StdResult<u64> {
         match prev {
             Some(idx) => Ok(idx + 1),
             None => Ok(0),
         }
     })?;
-    FOLLOWERS.save(deps.storage, (address, follower_idx), &info.sender)?;
+    FOLLOWERS.save(storage, (address.clone(), follower_idx), follower)?;
+    FOLLOWER_ADDRS.save(storage, (address.clone(), follower.clone()), &follower_idx)?;
+    FOLLOWER_KEY.save(storage, follower.clone(), &follower_idx)
+}
+
+fn remove_follower(storage: &mut dyn Storage, address: &Addr, follower: &Addr) -> StdResult<()> {
+    let follower_idx = match FOLLOWER_KEY.may_load(storage, follower.clone())? {
+        Some(idx) => Some(idx),
+        // 인덱스를 저장하기 전에 팔로잉한 경우
+        None => FOLLOWERS.prefix(address.clone()).range(storage, None, None, Order::Ascending)
+            .find(|item| matches!(item, Ok((_, addr)) if addr == follower))
+            .transpose()?
+            .map(|(idx, _)| idx),
+    };
+    if let Some(idx) = follower_idx {
+        FOLLOWERS.remove(storage, (address.clone(), idx));
+    }
+    FOLLOWER_ADDRS.remove(storage, (address.clone(), follower.clone()));
+    FOLLOWER_KEY.remove(storage, follower.clone());
+    Ok(())
+}
+
+// adds (or removes) the address with its own downline to the counts of each referrer above it
+fn update_downline(storage: &mut dyn Storage, addr: &Addr, add: bool) -> StdResult<()> {
+    let levels = STATE.load(storage)?.referral_ratio.len();
+    let own = DOWNLINE.may_load(storage, addr.clone())?.unwrap_or_default();
+    let mut upline = FOLLOWING.may_load(storage, addr.clone())?;
+    let mut level = 0;
+    while let Some(referrer) = upline {
+        if level >= levels {
+            break;
+        }
+        let mut counts = DOWNLINE.may_load(storage, referrer.clone())?.unwrap_or_default();
+        counts.resize(levels, 0);
+        // the address itself, then its followers one level further
+        let added = std::iter::once(1).chain(own.iter().copied());
+        for (count, amount) in counts.iter_mut().skip(level).zip(added) {
+            *count = if add { *count + amount } else { count.saturating_sub(amount) };
+        }
+        DOWNLINE.save(storage, referrer.clone(), &counts)?;
+        upline = FOLLOWING.may_load(storage, referrer)?;
+        level += 1;
+    }
+    Ok(())
+}
+
+pub fn execute_update_tiers(deps: DepsMut, info: MessageInfo, window: u64, tiers: Vec<Tier>) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.gov_contract != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let return_ratio = state.referral_ratio.first().copied().unwrap_or_default();
+    for (idx, tier) in tiers.iter().enumerate() {
+        if idx > 0 && tier.min_volume <= tiers[idx - 1].min_volume {
+            return Err(ContractError::InvalidTier {});
+        }
+        let total = tier.level_ratios.iter().fold(return_ratio, |total, ratio| total + *ratio);
+        if total > Decimal::one() {
+            return Err(ContractError::InvalidTier {});
+        }
+    }
+    TIERS.save(deps.storage, &TierConfig { window, tiers })?;
     Ok(Response::new()
-        .add_attribute("method", "add_following"))
+        .add_attribute("method", "update_tiers")
+        .add_attribute("window", window.to_string()))
 }
 
-pub fn execute_register_referral(deps: DepsMut, info: MessageInfo, addr: Option<Addr>, name: Option<String>, description: Option<String>, sender: Addr, amount: Uint128) -> Result<Response, ContractError> {
+pub fn execute_update_qualification(deps: DepsMut, info: MessageInfo, qualification: Qualification) -> Result<Response, ContractError> {
     let state: State = STATE.load(deps.storage)?;
+    if state.gov_contract != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    QUALIFICATION.save(deps.storage, &qualification)?;
+    Ok(Response::new().add_attribute("method", "update_qualification"))
+}
+
+pub fn execute_set_kickback(deps: DepsMut, info: MessageInfo, ratio: Decimal) -> Result<Response, ContractError> {
+    if !REFERRALS.has(deps.storage, info.sender.clone()) {
+        return Err(ContractError::NotReferral {});
+    }
+    if ratio > Decimal::one() {
+        return Err(ContractError::InvalidRatio {});
+    }
+    KICKBACKS.save(deps.storage, info.sender, &ratio)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_kickback")
+        .add_attribute("ratio", ratio.to_string()))
+}
+
+pub fn execute_add_following_by_code(deps: DepsMut, env: Env, info: MessageInfo, code: String) -> Result<Response, ContractError> {
+    let address = REFERRAL_CODES.may_load(deps.storage, &code.to_lowercase())?.ok_or(ContractError::NotReferral {})?;
+    execute_add_following(deps, env, info, address)
+}
+
+pub fn execute_register_referral(deps: DepsMut, env: Env, info: MessageInfo, addr: Option<Addr>, name: Option<String>, description: Option<String>, code: Option<String>, sender: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    let mut state: State = STATE.load(deps.storage)?;
     if state.zerosum_token != info.sender {
         return Err(ContractError::Unauthorized {});
     }
     if amount < state.register_referrer_fee {
         return Err(ContractError::NotEnough {});
     }
-    REFERRALS.update(deps.storage, addr.unwrap_or(sender), |prev| {
+    let address = addr.unwrap_or(sender);
+    // 팔로워를 다 옮기기 전에는 다시 등록할 수 없음
+    if DEREGISTERED.has(deps.storage, address.clone()) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    let code = match code {
+        Some(code) => Some(claim_code(deps.storage, &code, &address)?),
+        None => None,
+    };
+    REFERRALS.update(deps.storage, address, |prev| {
         match prev {
             Some(_) => Err(ContractError::AlreadyExist {}),
             None => Ok(Referral {
                 name: name.unwrap_or_default(),
                 description: description.unwrap_or_default(),
+                code: code.clone(),
+                fee: amount,
+                registered_round: get_round(env.block.height),
             })
         }
     })?;
+    state.total_fees += amount;
+    STATE.save(deps.storage, &state)?;
+    Ok(Response::new()
+        .add_attribute("method", "register_referral")
+        .add_attribute("code", code.unwrap_or_default()))
+}
+
+pub fn execute_deregister(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state: State = STATE.load(deps.storage)?;
+    let referral = REFERRALS.may_load(deps.storage, info.sender.clone())?.ok_or(ContractError::NotReferral {})?;
+    let current_round = get_round(env.block.height).unwrap_or_default();
+
+    // 팔로워들은 상위 레퍼럴을 팔로잉, 남은 팔로워는 MigrateFollowers 로
+    DEREGISTERED.save(deps.storage, info.sender.clone(), &current_round)?;
+    let (_, remaining) = migrate_followers(deps.storage, &info.sender, DEFAULT_MIGRATE_LIMIT as usize)?;
+
+    if let Some(code) = referral.code {
+        REFERRAL_CODES.remove(deps.storage, &code);
+    }
+    KICKBACKS.remove(deps.storage, info.sender.clone());
+    REFERRALS.remove(deps.storage, info.sender.clone());
+
+    let tenure_over = referral.registered_round.map_or(true, |round| round + state.deregister_tenure <= current_round);
+    let refund = if tenure_over { referral.fee * state.deregister_refund_ratio } else { Uint128::zero() };
+    let burn = referral.fee - refund;
+    state.total_fees = state.total_fees.saturating_sub(referral.fee);
+    STATE.save(deps.storage, &state)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !refund.is_zero() {
+        msgs.push(token_asset(state.zerosum_token.clone(), refund).into_msg(&deps.querier, info.sender.clone())?);
+    }
+    if !burn.is_zero() {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: state.zerosum_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount: burn })?,
+            funds: vec![],
+        }));
+    }
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "deregister")
+        .add_attribute("refund", refund)
+        .add_attribute("burn", burn)
+        .add_attribute("remaining", remaining.to_string()))
+}
+
+pub fn execute_migrate_followers(deps: DepsMut, referrer: Addr, limit: Option<u64>) -> Result<Response, ContractError> {
+    if !DEREGISTERED.has(deps.storage, referrer.clone()) {
+        return Err(ContractError::NotExist {});
+    }
+    let (migrated, remaining) = migrate_followers(deps.storage, &referrer, limit.unwrap_or(DEFAULT_MIGRATE_LIMIT) as usize)?;
     Ok(Response::new()
-        .add_attribute("method", "register_referral"))
+        .add_attribute("method", "migrate_followers")
+        .add_attribute("referrer", referrer)
+        .add_attribute("migrated", migrated.to_string())
+        .add_attribute("remaining", remaining.to_string()))
+}
+
+// 탈퇴한 레퍼럴의 팔로워를 `limit` 명까지 상위 레퍼럴로 옮김, 팔로잉한 라운드는 그대로.
+// 옮긴 수와 남은 팔로워가 있는지 반환
+fn migrate_followers(storage: &mut dyn Storage, referrer: &Addr, limit: usize) -> StdResult<(usize, bool)> {
+    let to_round = DEREGISTERED.load(storage, referrer.clone())?;
+    let upline = active_referrer(storage, FOLLOWING.may_load(storage, referrer.clone())?)?;
+    let followers = FOLLOWERS.prefix(referrer.clone()).range(storage, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, Addr)>>>()?;
+    for (idx, follower) in followers.iter() {
+        update_downline(storage, follower, false)?;
+        FOLLOWERS.remove(storage, (referrer.clone(), *idx));
+        FOLLOWER_ADDRS.remove(storage, (referrer.clone(), follower.clone()));
+        // 탈퇴 전 라운드의 리워드는 탈퇴한 레퍼럴에게
+        let history_idx = FOLLOWING_HISTORY.prefix(follower.clone()).keys(storage, None, None, Order::Ascending).count() as u64;
+        FOLLOWING_HISTORY.save(storage, (follower.clone(), history_idx), &FollowingHistory {
+            referrer: referrer.clone(),
+            to_round,
+        })?;
+        match &upline {
+            Some(upline) => {
+                FOLLOWING.save(storage, follower.clone(), upline)?;
+                add_follower(storage, upline, follower)?;
+                update_downline(storage, follower, true)?;
+            },
+            None => {
+                FOLLOWING.remove(storage, follower.clone());
+                FOLLOWER_KEY.remove(storage, follower.clone());
+            },
+        }
+    }
+
+    let remaining = FOLLOWERS.prefix(referrer.clone()).keys(storage, None, None, Order::Ascending).next().is_some();
+    if !remaining {
+        DEREGISTERED.remove(storage, referrer.clone());
+        DOWNLINE.remove(storage, referrer.clone());
+    }
+    Ok((followers.len(), remaining))
+}
+
+// 아직 옮겨지지 않은 팔로워는 탈퇴한 레퍼럴 대신 그 위 레퍼럴을 따름
+fn active_referrer(storage: &dyn Storage, referrer: Option<Addr>) -> StdResult<Option<Addr>> {
+    let mut referrer = referrer;
+    while let Some(addr) = referrer.clone() {
+        if !DEREGISTERED.has(storage, addr.clone()) {
+            break;
+        }
+        referrer = FOLLOWING.may_load(storage, addr)?;
+    }
+    Ok(referrer)
+}
+
+pub fn execute_rename_code(deps: DepsMut, info: MessageInfo, code: String, sender: Addr, amount: Uint128) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.zerosum_token != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount < state.rename_code_fee {
+        return Err(ContractError::NotEnough {});
+    }
+    let mut referral = REFERRALS.may_load(deps.storage, sender.clone())?.ok_or(ContractError::NotReferral {})?;
+    let code = claim_code(deps.storage, &code, &sender)?;
+    if let Some(prev_code) = referral.code {
+        REFERRAL_CODES.remove(deps.storage, &prev_code);
+    }
+    referral.code = Some(code.clone());
+    REFERRALS.save(deps.storage, sender, &referral)?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        msgs.push(token_asset(state.zerosum_token, amount).into_msg(&deps.querier, state.collector_contract)?);
+    }
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "rename_code")
+        .add_attribute("code", code))
+}
+
+// codes are 3 to 20 lowercase letters, digits, `-` or `_` so they fit in a link
+fn claim_code(storage: &mut dyn Storage, code: &str, address: &Addr) -> Result<String, ContractError> {
+    let code = code.to_lowercase();
+    if code.len() < 3 || code.len() > 20 || !code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(ContractError::InvalidCode {});
+    }
+    if REFERRAL_CODES.has(storage, &code) {
+        return Err(ContractError::AlreadyExist {});
+    }
+    REFERRAL_CODES.save(storage, &code, address)?;
+    Ok(code)
 }
 
 pub fn execute_add_round(deps: DepsMut, info: MessageInfo, round: u64, sender: Addr, amount:Uint128) -> Result<Response, ContractError> {
@@ -213,7 +575,8 @@ pub fn execute_add_round(deps: DepsMut, info: MessageInfo, round: u64, sender: A
     if !total_share.is_zero() {
         let reward_ratio = Decimal::from_ratio(amount, total_share);
         ROUNDS.save(deps.storage, round, &RoundInfo {
-            reward_ratio
+            reward_ratio,
+            unclaimed: Some(amount),
         })?;
         msgs.push(token_asset(state.zerosum_token, amount).into_msg(&deps.querier, state.reward_contract)?);
     }
@@ -232,123 +595,431 @@ pub fn execute_add_share(deps: DepsMut, env: Env, info: MessageInfo, address: Ad
     }
     let current_round = get_round(env.block.height);
     if current_round.is_some() {
-        REWARD_SHARE.update(deps.storage, (current_round.unwrap(), address), |prev| -> Result<Uint128, ContractError> {
-            match prev {
-                Some(prev_amount) => Ok(prev_amount + amount),
-                None => Ok(amount),
-            }
-        })?;
+        let prev_share = REWARD_SHARE.may_load(deps.storage, (current_round.unwrap(), address.clone()))?.unwrap_or_default();
+        REWARD_SHARE.save(deps.storage, (current_round.unwrap(), address.clone()), &(prev_share + amount))?;
         TOTAL_REWARD_SHARE.update(deps.storage, current_round.unwrap(), |prev| -> Result<Uint128, ContractError> {
             match prev {
                 Some(prev_amount) => Ok(prev_amount + amount),
                 None => Ok(amount),
             }
         })?;
+        if let Some(referrer) = active_referrer(deps.storage, FOLLOWING.may_load(deps.storage, address.clone())?)? {
+            VOLUME_BOARD.add(deps.storage, &referrer, current_round.unwrap(), amount)?;
+            VOLUME_BOARD.add(deps.storage, &referrer, ALL_TIME, amount)?;
+            // 이번 라운드에 처음 `min_follower_volume` 을 넘은 팔로워
+            let qualification = QUALIFICATION.may_load(deps.storage)?.unwrap_or_default();
+            if qualification.min_active_followers > 0
+                && prev_share < qualification.min_follower_volume
+                && prev_share + amount >= qualification.min_follower_volume {
+                let key = (referrer, current_round.unwrap());
+                let active_followers = ACTIVE_FOLLOWERS.may_load(deps.storage, key.clone())?.unwrap_or_default();
+                ACTIVE_FOLLOWERS.save(deps.storage, key, &(active_followers + 1))?;
+            }
+        }
     }
     Ok(Response::new().add_attribute("method", "add_share"))
 }
 
-pub fn execute_claim_referral(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn execute_claim_referral(deps: DepsMut, env: Env, owner: Addr, recipient: Addr) -> Result<Response, ContractError> {
     let mut msgs: Vec<CosmosMsg> = vec![];
     let mut reward: Uint128 = Uint128::zero();
+    let mut expired: Uint128 = Uint128::zero();
+    let mut assets: Vec<Asset> = vec![];
     let state: State = STATE.load(deps.storage)?;
-    REWARDS.update(deps.storage, info.sender.clone(), |prev| {
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    REWARDS.update(deps.storage, owner, |prev| {
         match prev {
             Some(mut prev_reward) => {
-                reward = prev_reward.claimable_reward;
+                // 만료된 리워드는 컬렉터로, 자산은 만료되지 않음
+                if reward_expired(&state, &prev_reward, current_round) {
+                    expired = prev_reward.claimable_reward;
+                } else {
+                    reward = prev_reward.claimable_reward;
+                }
                 prev_reward.claimable_reward = Uint128::zero();
+                prev_reward.last_round = Some(current_round);
+                assets = std::mem::take(&mut prev_reward.claimable_assets);
                 Ok(prev_reward)
             },
             None => Err(ContractError::NotEnough {})
         }
     })?;
+    if !expired.is_zero() {
+        msgs.push(reward_msg(state.reward_contract.clone(), state.collector_contract, expired)?);
+    }
     if !reward.is_zero() {
-        msgs.push(reward_msg(state.reward_contract, info.sender, reward)?);
+        msgs.push(reward_msg(state.reward_contract, recipient.clone(), reward)?);
+    }
+    for asset in assets {
+        if !asset.amount.is_zero() {
+            msgs.push(asset.into_msg(&deps.querier, recipient.clone())?);
+        }
     }
     
     Ok(Response::new().add_messages(msgs)
         .add_attribute("method", "claim_referral")
-        .add_attribute("reward", reward))
+        .add_attribute("reward", reward)
+        .add_attribute("expired", expired))
 }
 
-pub fn execute_claim(deps: DepsMut, env: Env, info: MessageInfo, start_round_param: Option<u64>) -> Result<Response, ContractError> {
+pub fn execute_claim(mut deps: DepsMut, env: Env, owner: Addr, recipient: Addr, start_round_param: Option<u64>, max_rounds: Option<u64>) -> Result<Response, ContractError> {
     let state: State = STATE.load(deps.storage)?;
     let current_round = get_round(env.block.height).expect("NOT STARTED ROUND");
-    let last_claim_round = LAST_CLAIM_ROUND.may_load(deps.storage, info.sender.clone())?;
-    let start_round = if last_claim_round.is_some() {
-        last_claim_round.unwrap() + 1
-    } else {
-        start_round_param.unwrap_or_default()
-    };
-    LAST_CLAIM_ROUND.save(deps.storage, info.sender.clone(), &(current_round - 1))?;
-    
+    // 만료된 라운드는 스윕 대상
+    let start_round = std::cmp::max(claim_start_round(deps.storage, &owner, start_round_param)?, expire_before(&state, current_round));
+    let max_rounds = max_rounds.map(|max_rounds| std::cmp::max(max_rounds, 1));
+
     let start = Some(Bound::InclusiveRaw(start_round.to_be_bytes().to_vec()));
     let end = Some(Bound::ExclusiveRaw(current_round.to_be_bytes().to_vec()));
-    
-    let mut reward = Uint128::zero();
+    let rounds = ROUNDS.range(deps.storage, start, end, Order::Ascending)
+        .take(max_rounds.unwrap_or(u64::MAX) as usize)
+        .collect::<StdResult<Vec<(u64, RoundInfo)>>>()?;
+    // 남은 라운드는 다음 클레임에서
+    let last_claim_round = match (max_rounds, rounds.last()) {
+        (Some(max_rounds), Some((key, _))) if rounds.len() as u64 == max_rounds => *key,
+        _ => current_round - 1,
+    };
+    LAST_CLAIM_ROUND.save(deps.storage, owner.clone(), &last_claim_round)?;
 
-    ROUNDS.range(deps.storage, start, end, Order::Ascending).for_each(|item| {
-        let (key, round_info) = item.unwrap();
-        let my_share = REWARD_SHARE.may_load(deps.storage, (key, info.sender.clone())).unwrap();
+    // 라운드 당시의 팔로잉에게 분배
+    let mut rewards: Vec<(Option<Addr>, Vec<Uint128>)> = vec![];
+    for (key, mut round_info) in rounds {
+        let my_share = REWARD_SHARE.may_load(deps.storage, (key, owner.clone()))?;
         if my_share.is_some() {
-            reward = reward + (my_share.unwrap() * round_info.reward_ratio);
+            let amount = my_share.unwrap() * round_info.reward_ratio;
+            if let Some(unclaimed) = round_info.unclaimed {
+                round_info.unclaimed = Some(unclaimed.saturating_sub(amount));
+                ROUNDS.save(deps.storage, key, &round_info)?;
+            }
+            let referrer = referrer_at(deps.storage, &owner, key)?;
+            match rewards.last_mut() {
+                Some((last_referrer, round_rewards)) if *last_referrer == referrer => round_rewards.push(amount),
+                _ => rewards.push((referrer, vec![amount])),
+            }
         }
-    });
+    }
 
     let mut msgs = vec![];
-    if !reward.is_zero() {
-        let mut iter = state.referral_ratio.iter();
-        let return_amount = reward * *iter.next().unwrap();
-        let mut rest_reward = reward - return_amount;
-        let mut target_address = info.sender.clone();
-        loop {
-            let ratio = iter.next();
-            if ratio.is_some() {
-                let reward_amount = reward * *ratio.unwrap();
-                rest_reward = rest_reward - reward_amount;
-                REWARDS.update(deps.storage, target_address.clone(), |old| -> Result<Reward, ContractError> {
-                    match old {
-                        Some(mut prev_reward) => {
-                            prev_reward.total_reward += reward_amount;
-                            prev_reward.claimable_reward += reward_amount;
-                            Ok(prev_reward)
-                        },
-                        None => Ok(Reward {
-                            claimable_reward: reward_amount,
-                            total_reward: reward_amount
-                        }),
-                    }
-                })?;
-                REFERRAL_HISTORY.update(deps.storage, (target_address.clone(), info.sender.clone()), |prev| -> Result<Uint128, ContractError> {
-                    match prev {
-                        Some(prev_amount) => Ok(prev_amount + reward_amount),
-                        None => Ok(reward_amount)
-                    }
-                })?;
-
-                let next_addr = FOLLOWING.may_load(deps.storage, target_address.clone()).unwrap();
-                if next_addr.is_some() {
-                    target_address = next_addr.unwrap();
+    let mut return_amount = Uint128::zero();
+    let mut rest_reward = Uint128::zero();
+    let mut disqualified_reward = Uint128::zero();
+    for (referrer, round_rewards) in rewards {
+        let reward = round_rewards.iter().fold(Uint128::zero(), |total, amount| total + *amount);
+        if reward.is_zero() {
+            continue;
+        }
+        let (returned, rest, disqualified) = distribute_reward(deps.branch(), &state, &owner, referrer, reward, &round_rewards, current_round)?;
+        return_amount += returned;
+        rest_reward += rest;
+        disqualified_reward += disqualified;
+    }
+    if !disqualified_reward.is_zero() {
+        msgs.push(reward_msg(state.reward_contract.clone(), state.collector_contract.clone(), disqualified_reward)?);
+    }
+    if !return_amount.is_zero() || !rest_reward.is_zero() {
+        if !rest_reward.is_zero() {
+            // 남은 리워드 다시 반환 (referral_contract의 수익금)
+            msgs.push(reward_msg(state.reward_contract.clone(), env.contract.address, rest_reward)?);
+        }
+        msgs.push(reward_msg(state.reward_contract, recipient, return_amount)?);
+    }
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("method", "claim")
+        .add_attribute("last_claim_round", last_claim_round.to_string()))
+}
+
+pub fn execute_set_claim_delegate(deps: DepsMut, info: MessageInfo, delegate: Option<String>) -> Result<Response, ContractError> {
+    let delegate = set_claim_delegate(deps.storage, deps.api, &CLAIM_DELEGATES, info.sender, delegate)?;
+    Ok(Response::new()
+        .add_attribute("method", "set_claim_delegate")
+        .add_attribute("delegate", delegate.map(String::from).unwrap_or_default()))
+}
+
+fn claim_start_round(storage: &dyn Storage, addr: &Addr, start_round_param: Option<u64>) -> StdResult<u64> {
+    let last_claim_round = LAST_CLAIM_ROUND.may_load(storage, addr.clone())?;
+    if last_claim_round.is_some() {
+        Ok(last_claim_round.unwrap() + 1)
+    } else {
+        Ok(start_round_param.unwrap_or_default())
+    }
+}
+
+// splits the reward of `rounds` rounds of the claimer along the upline starting at `referrer`,
+// returns the claimer part, the rest and the part of the referrers not qualified or expired
+fn distribute_reward(deps: DepsMut, state: &State, sender: &Addr, referrer: Option<Addr>, reward: Uint128, round_rewards: &[Uint128], current_round: u64) -> Result<(Uint128, Uint128, Uint128), ContractError> {
+    let qualification = QUALIFICATION.may_load(deps.storage)?.unwrap_or_default();
+    let mut disqualified = Uint128::zero();
+    let mut iter = state.referral_ratio.iter();
+    let mut return_amount = reward * *iter.next().unwrap();
+    let mut rest_reward = reward - return_amount;
+    let mut target_address = sender.clone();
+    let mut level = 0;
+    loop {
+        let ratio = iter.next();
+        if ratio.is_some() {
+            level += 1;
+            let ratio = level_ratio(deps.as_ref(), &target_address, current_round, level, *ratio.unwrap())?;
+            let mut reward_amount = std::cmp::min(reward * ratio, rest_reward);
+            rest_reward = rest_reward - reward_amount;
+            if target_address != *sender {
+                let direct = Some(&target_address) == referrer.as_ref();
+                let earned = if is_qualified(deps.as_ref(), state, &qualification, &target_address, direct, current_round)? {
+                    capped_reward(&qualification, reward_amount, round_rewards, ratio)
                 } else {
-                    break;
+                    Uint128::zero()
+                };
+                disqualified += reward_amount - earned;
+                reward_amount = earned;
+            }
+            if Some(&target_address) == referrer.as_ref() {
+                let kickback = reward_amount * KICKBACKS.may_load(deps.storage, target_address.clone())?.unwrap_or_default();
+                if !kickback.is_zero() {
+                    reward_amount = reward_amount - kickback;
+                    return_amount += kickback;
+                    let history = KICKBACK_HISTORY.may_load(deps.storage, (sender.clone(), target_address.clone()))?.unwrap_or_default();
+                    KICKBACK_HISTORY.save(deps.storage, (sender.clone(), target_address.clone()), &(history + kickback))?;
+                }
+            }
+            REWARDS.update(deps.storage, target_address.clone(), |old| -> Result<Reward, ContractError> {
+                match old {
+                    Some(mut prev_reward) => {
+                        // 만료된 잔액은 새 리워드로 되살리지 않음
+                        if reward_expired(state, &prev_reward, current_round) {
+                            disqualified += prev_reward.claimable_reward;
+                            prev_reward.claimable_reward = Uint128::zero();
+                        }
+                        prev_reward.total_reward += reward_amount;
+                        prev_reward.claimable_reward += reward_amount;
+                        prev_reward.last_round = Some(current_round);
+                        Ok(prev_reward)
+                    },
+                    None => Ok(Reward {
+                        claimable_reward: reward_amount,
+                        total_reward: reward_amount,
+                        last_round: Some(current_round),
+                        claimable_assets: vec![],
+                        total_assets: vec![],
+                    }),
+                }
+            })?;
+            if !reward_amount.is_zero() {
+                REWARD_BOARD.add(deps.storage, &target_address, current_round, reward_amount)?;
+                REWARD_BOARD.add(deps.storage, &target_address, ALL_TIME, reward_amount)?;
+            }
+            REFERRAL_HISTORY.update(deps.storage, (target_address.clone(), sender.clone()), |prev| -> Result<Uint128, ContractError> {
+                match prev {
+                    Some(prev_amount) => Ok(prev_amount + reward_amount),
+                    None => Ok(reward_amount)
                 }
+            })?;
+
+            let next_addr = if target_address == *sender {
+                referrer.clone()
+            } else {
+                active_referrer(deps.storage, FOLLOWING.may_load(deps.storage, target_address.clone())?)?
+            };
+            if next_addr.is_some() {
+                target_address = next_addr.unwrap();
             } else {
                 break;
             }
+        } else {
+            break;
         }
-        if !rest_reward.is_zero() {
-            // 남은 리워드 다시 반환 (referral_contract의 수익금)
-            msgs.push(reward_msg(state.reward_contract.clone(), env.contract.address, rest_reward)?);
+    }
+    Ok((return_amount, rest_reward, disqualified))
+}
+
+// a referrer earns from its direct followers with `min_balance`, and from the rest of the downline with `min_active_followers` too
+fn is_qualified(deps: Deps, state: &State, qualification: &Qualification, referrer: &Addr, direct: bool, current_round: u64) -> StdResult<bool> {
+    let (balance, active_followers) = referrer_activity(deps, state, qualification, referrer, current_round)?;
+    if balance < qualification.min_balance {
+        return Ok(false);
+    }
+    Ok(direct || active_followers >= qualification.min_active_followers)
+}
+
+// ZEROSUM balance and active followers of the last round
+fn referrer_activity(deps: Deps, state: &State, qualification: &Qualification, referrer: &Addr, current_round: u64) -> StdResult<(Uint128, u64)> {
+    let balance = if qualification.min_balance.is_zero() {
+        Uint128::zero()
+    } else {
+        token_asset_info(state.zerosum_token.clone()).query_balance(&deps.querier, referrer.clone())?
+    };
+    let active_followers = if current_round == 0 {
+        0
+    } else {
+        ACTIVE_FOLLOWERS.may_load(deps.storage, (referrer.clone(), current_round - 1))?.unwrap_or_default()
+    };
+    Ok((balance, active_followers))
+}
+
+// 한도는 라운드마다 적용
+fn capped_reward(qualification: &Qualification, reward: Uint128, round_rewards: &[Uint128], ratio: Decimal) -> Uint128 {
+    if qualification.max_follower_reward.is_zero() {
+        return reward;
+    }
+    let capped = round_rewards.iter().fold(Uint128::zero(), |total, round_reward| {
+        total + std::cmp::min(*round_reward * ratio, qualification.max_follower_reward)
+    });
+    std::cmp::min(reward, capped)
+}
+
+// referrer the address followed during the round, the first one also covers the rounds before following
+fn referrer_at(storage: &dyn Storage, addr: &Addr, round: u64) -> StdResult<Option<Addr>> {
+    for item in FOLLOWING_HISTORY.prefix(addr.clone()).range(storage, None, None, Order::Ascending) {
+        let (_, history) = item?;
+        if round < history.to_round {
+            return Ok(Some(history.referrer));
         }
-        msgs.push(reward_msg(state.reward_contract, info.sender, return_amount)?);
     }
-    Ok(Response::new().add_messages(msgs).add_attribute("method", "claim"))
+    let referrer = match FOLLOWING.may_load(storage, addr.clone())? {
+        Some(referrer) => referrer,
+        None => return Ok(None),
+    };
+    match DEREGISTERED.may_load(storage, referrer.clone())? {
+        Some(to_round) if round >= to_round => active_referrer(storage, Some(referrer)),
+        _ => Ok(Some(referrer)),
+    }
+}
+
+// ratio of the referrer at `level` of the upline, taken from its tier when it has one
+fn level_ratio(deps: Deps, referrer: &Addr, current_round: u64, level: usize, default_ratio: Decimal) -> StdResult<Decimal> {
+    let (_, tier) = referrer_tier(deps, referrer, current_round)?;
+    Ok(tier.and_then(|tier| tier.level_ratios.get(level - 1).copied()).unwrap_or(default_ratio))
+}
+
+// volume of the followers over the window before the current round and the highest tier it reaches
+fn referrer_tier(deps: Deps, referrer: &Addr, current_round: u64) -> StdResult<(Uint128, Option<Tier>)> {
+    let config = match TIERS.may_load(deps.storage)? {
+        Some(config) => config,
+        None => return Ok((Uint128::zero(), None)),
+    };
+    let volume = VOLUME_BOARD.sum(deps.storage, referrer, current_round.saturating_sub(config.window), current_round)?;
+    let tier = config.tiers.into_iter().filter(|tier| tier.min_volume <= volume).last();
+    Ok((volume, tier))
+}
+
+// splits the edge over the referrers above the player with the weights of `referral_ratio[2..]`, the rest goes to the collector
+// 자격 없는 레퍼럴 몫은 collector 로.
+// max_follower_reward 는 라운드별 ZEROSUM 리워드 한도라서 베팅 자산으로 주는 edge 에는 적용하지 않음
+fn execute_add_edge(deps: DepsMut, env: Env, sender: Addr, address: Addr, asset: Asset) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.house_coutract != sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let qualification = QUALIFICATION.may_load(deps.storage)?.unwrap_or_default();
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let ratios = state.referral_ratio.get(2..).unwrap_or_default();
+    let total_ratio = ratios.iter().fold(Decimal::zero(), |total, ratio| total + *ratio);
+
+    let mut rest = asset.amount;
+    let mut target_address = address;
+    for (level, ratio) in ratios.iter().enumerate() {
+        let referrer = active_referrer(deps.storage, FOLLOWING.may_load(deps.storage, target_address.clone())?)?;
+        if referrer.is_none() {
+            break;
+        }
+        target_address = referrer.unwrap();
+        let amount = std::cmp::min(asset.amount.multiply_ratio(ratio.atomics(), total_ratio.atomics()), rest);
+        if amount.is_zero() || !is_qualified(deps.as_ref(), &state, &qualification, &target_address, level == 0, current_round)? {
+            continue;
+        }
+        rest = rest - amount;
+        let mut reward = REWARDS.may_load(deps.storage, target_address.clone())?.unwrap_or_default();
+        add_asset(&mut reward.claimable_assets, &asset.info, amount);
+        add_asset(&mut reward.total_assets, &asset.info, amount);
+        REWARDS.save(deps.storage, target_address.clone(), &reward)?;
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !rest.is_zero() {
+        msgs.push(Asset {
+            info: asset.info.clone(),
+            amount: rest,
+        }.into_msg(&deps.querier, state.collector_contract)?);
+    }
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "add_edge")
+        .add_attribute("asset", asset.to_string())
+        .add_attribute("rest", rest))
+}
+
+fn add_asset(assets: &mut Vec<Asset>, info: &AssetInfo, amount: Uint128) {
+    match assets.iter_mut().find(|asset| asset.info == *info) {
+        Some(asset) => asset.amount += amount,
+        None => assets.push(Asset {
+            info: info.clone(),
+            amount,
+        }),
+    }
+}
+
+fn execute_sweep_expired(deps: DepsMut, env: Env, referrers: Option<Vec<Addr>>, limit: Option<u64>) -> Result<Response, ContractError> {
+    let state: State = STATE.load(deps.storage)?;
+    if state.reward_expiry == 0 {
+        return Err(ContractError::Unauthorized {});
+    }
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let mut amount = Uint128::zero();
+
+    let sweep_round = SWEEP_ROUND.may_load(deps.storage)?.unwrap_or_default();
+    let start = Some(Bound::InclusiveRaw(sweep_round.to_be_bytes().to_vec()));
+    let end = Some(Bound::ExclusiveRaw(expire_before(&state, current_round).to_be_bytes().to_vec()));
+    let rounds = ROUNDS.range(deps.storage, start, end, Order::Ascending)
+        .take(limit.unwrap_or(20) as usize)
+        .collect::<StdResult<Vec<(u64, RoundInfo)>>>()?;
+    for (key, mut round_info) in rounds {
+        amount += round_info.unclaimed.unwrap_or_default();
+        round_info.unclaimed = Some(Uint128::zero());
+        ROUNDS.save(deps.storage, key, &round_info)?;
+        SWEEP_ROUND.save(deps.storage, &(key + 1))?;
+    }
+
+    for referrer in referrers.unwrap_or_default() {
+        let reward = REWARDS.may_load(deps.storage, referrer.clone())?;
+        if let Some(mut reward) = reward {
+            if reward_expired(&state, &reward, current_round) {
+                amount += reward.claimable_reward;
+                reward.claimable_reward = Uint128::zero();
+                reward.last_round = Some(current_round);
+                REWARDS.save(deps.storage, referrer, &reward)?;
+            }
+        }
+    }
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        msgs.push(reward_msg(state.reward_contract, state.collector_contract, amount)?);
+    }
+    Ok(Response::new().add_messages(msgs)
+        .add_attribute("method", "sweep_expired")
+        .add_attribute("amount", amount))
+}
+
+// rounds before it are expired
+fn expire_before(state: &State, current_round: u64) -> u64 {
+    if state.reward_expiry == 0 {
+        0
+    } else {
+        current_round.saturating_sub(state.reward_expiry)
+    }
+}
+
+fn reward_expired(state: &State, reward: &Reward, current_round: u64) -> bool {
+    match reward.last_round {
+        Some(last_round) if state.reward_expiry != 0 => last_round + state.reward_expiry < current_round,
+        _ => false,
+    }
 }
 
 fn execute_collect(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let state: State = STATE.load(deps.storage)?;
     let mut msgs: Vec<CosmosMsg> = vec![];
-    let balance = token_asset_info(state.zerosum_token.clone()).query_balance(&deps.querier, env.contract.address)?;
+    // 등록비는 레퍼럴이 해지할 때까지 보관
+    let balance = token_asset_info(state.zerosum_token.clone()).query_balance(&deps.querier, env.contract.address)?
+        .saturating_sub(state.total_fees);
     if !balance.is_zero() {
         msgs.push(token_asset(state.zerosum_token, balance).into_msg(&deps.querier, state.collector_contract).unwrap())
     }
@@ -370,6 +1041,23 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetReferralReward { addr } => to_binary(&query_referral_reward(deps, env, addr)?),
         QueryMsg::GetReward { addr } => to_binary(&query_reward(deps, env, addr)?),
         QueryMsg::GetLastClaimRound { addr } => to_binary(&query_last_claim_round(deps, env, addr)?),
+        QueryMsg::EstimateClaim { addr, start_round, max_rounds } => to_binary(&query_estimate_claim(deps, env, addr, start_round, max_rounds)?),
+        QueryMsg::ReferralByCode { code } => to_binary(&query_referral_by_code(deps, code)?),
+        QueryMsg::GetTiers {} => to_binary(&query_tiers(deps)?),
+        QueryMsg::GetReferrerTier { addr } => to_binary(&query_referrer_tier(deps, env, addr)?),
+        QueryMsg::ListReferrals { start_after, limit } => to_binary(&query_list_referrals(deps, start_after, limit)?),
+        QueryMsg::ListFollowers { addr, start_after, limit } => to_binary(&query_list_followers(deps, addr, start_after, limit)?),
+        QueryMsg::GetUpline { addr } => to_binary(&query_upline(deps, addr)?),
+        QueryMsg::GetDownline { addr } => to_binary(&query_downline(deps, addr)?),
+        QueryMsg::GetFollowingHistory { addr } => to_binary(&query_following_history(deps, addr)?),
+        QueryMsg::GetExpiring { addr, start_round } => to_binary(&query_expiring(deps, env, addr, start_round)?),
+        QueryMsg::GetLeaderboard { board, round, limit } => to_binary(&query_leaderboard(deps, board, round, limit)?),
+        QueryMsg::GetClaimDelegate { addr } => to_binary(&CLAIM_DELEGATES.may_load(deps.storage, addr)?),
+        QueryMsg::GetQualification {} => to_binary(&QUALIFICATION.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::GetQualified { addr } => to_binary(&query_qualified(deps, env, addr)?),
+        QueryMsg::GetKickback { addr } => to_binary(&KICKBACKS.may_load(deps.storage, addr)?.unwrap_or_default()),
+        QueryMsg::GetKickbackHistory { follower, referrer } =>
+            to_binary(&KICKBACK_HISTORY.may_load(deps.storage, (follower, referrer))?.unwrap_or_default()),
     }
 }
 
@@ -382,12 +1070,153 @@ fn query_referral(deps: Deps, addr: Addr) -> StdResult<Referral> {
     Ok(REFERRALS.load(deps.storage, addr)?)
 }
 
-fn query_referrals(deps: Deps, start_after: Option<u64>, limit: Option<u64>) -> StdResult<Vec<Referral>> {
-    let start = if start_after.is_some() {
-        Some(Bound::ExclusiveRaw(start_after.unwrap().to_be_bytes().to_vec()))
-    } else {
-        None
+fn query_referral_by_code(deps: Deps, code: String) -> StdResult<ReferralCodeResponse> {
+    let code = code.to_lowercase();
+    let address = REFERRAL_CODES.load(deps.storage, &code)?;
+    let referral = REFERRALS.load(deps.storage, address.clone())?;
+    Ok(ReferralCodeResponse {
+        address,
+        code,
+        name: referral.name,
+        description: referral.description,
+    })
+}
+
+fn query_qualified(deps: Deps, env: Env, addr: Addr) -> StdResult<QualifiedResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let qualification = QUALIFICATION.may_load(deps.storage)?.unwrap_or_default();
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let (balance, active_followers) = referrer_activity(deps, &state, &qualification, &addr, current_round)?;
+    Ok(QualifiedResponse {
+        balance,
+        active_followers,
+        direct: is_qualified(deps, &state, &qualification, &addr, true, current_round)?,
+        upline: is_qualified(deps, &state, &qualification, &addr, false, current_round)?,
+    })
+}
+
+fn query_tiers(deps: Deps) -> StdResult<TiersResponse> {
+    let config = TIERS.may_load(deps.storage)?.unwrap_or(TierConfig { window: 0, tiers: vec![] });
+    Ok(TiersResponse {
+        window: config.window,
+        tiers: config.tiers,
+    })
+}
+
+fn query_referrer_tier(deps: Deps, env: Env, addr: Addr) -> StdResult<ReferrerTierResponse> {
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let (volume, tier) = referrer_tier(deps, &addr, current_round)?;
+    let next_tier = TIERS.may_load(deps.storage)?
+        .and_then(|config| config.tiers.into_iter().find(|tier| tier.min_volume > volume));
+    let remaining_volume = next_tier.as_ref().map(|tier| tier.min_volume - volume).unwrap_or_default();
+    Ok(ReferrerTierResponse {
+        volume,
+        tier,
+        next_tier,
+        remaining_volume,
+    })
+}
+
+fn query_list_referrals(deps: Deps, start_after: Option<Addr>, limit: Option<u64>) -> StdResult<Vec<ReferralInfo>> {
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.as_bytes().to_vec()));
+    REFERRALS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (address, referral) = item?;
+        Ok(ReferralInfo {
+            address,
+            name: referral.name,
+            description: referral.description,
+            code: referral.code,
+        })
+    }).collect()
+}
+
+fn query_list_followers(deps: Deps, addr: Addr, start_after: Option<Addr>, limit: Option<u64>) -> StdResult<Vec<FollowerInfo>> {
+    let start = start_after.map(|follower| Bound::ExclusiveRaw(follower.as_bytes().to_vec()));
+    FOLLOWER_ADDRS.prefix(addr.clone()).range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
+        let (follower, index) = item?;
+        let amount = REFERRAL_HISTORY.may_load(deps.storage, (addr.clone(), follower.clone()))?.unwrap_or_default();
+        Ok(FollowerInfo {
+            index,
+            address: follower,
+            amount,
+        })
+    }).collect()
+}
+
+fn query_upline(deps: Deps, addr: Addr) -> StdResult<Vec<Addr>> {
+    let levels = STATE.load(deps.storage)?.referral_ratio.len();
+    let mut upline: Vec<Addr> = vec![];
+    let mut next = FOLLOWING.may_load(deps.storage, addr)?;
+    while let Some(referrer) = next {
+        if upline.len() >= levels {
+            break;
+        }
+        next = FOLLOWING.may_load(deps.storage, referrer.clone())?;
+        upline.push(referrer);
+    }
+    Ok(upline)
+}
+
+fn query_downline(deps: Deps, addr: Addr) -> StdResult<DownlineResponse> {
+    let levels = STATE.load(deps.storage)?.referral_ratio.len();
+    let mut counts = DOWNLINE.may_load(deps.storage, addr)?.unwrap_or_default();
+    counts.resize(levels, 0);
+    Ok(DownlineResponse {
+        total: counts.iter().sum(),
+        levels: counts,
+    })
+}
+
+fn query_expiring(deps: Deps, env: Env, addr: Addr, start_round_param: Option<u64>) -> StdResult<ExpiringResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let reward = REWARDS.may_load(deps.storage, addr.clone())?;
+    let (claimable_reward, claimable_expire_round) = match reward {
+        Some(reward) if !reward_expired(&state, &reward, current_round) => (
+            reward.claimable_reward,
+            reward.last_round.filter(|_| state.reward_expiry != 0).map(|last_round| last_round + state.reward_expiry + 1),
+        ),
+        _ => (Uint128::zero(), None),
+    };
+
+    let mut rounds: Vec<ExpiringRound> = vec![];
+    if state.reward_expiry != 0 {
+        let start_round = std::cmp::max(claim_start_round(deps.storage, &addr, start_round_param)?, expire_before(&state, current_round));
+        let start = Some(Bound::InclusiveRaw(start_round.to_be_bytes().to_vec()));
+        let end = Some(Bound::ExclusiveRaw(current_round.to_be_bytes().to_vec()));
+        for item in ROUNDS.range(deps.storage, start, end, Order::Ascending) {
+            let (key, round_info) = item?;
+            let my_share = REWARD_SHARE.may_load(deps.storage, (key, addr.clone()))?;
+            if my_share.is_some() {
+                rounds.push(ExpiringRound {
+                    round: key,
+                    reward: my_share.unwrap() * round_info.reward_ratio,
+                    expire_round: key + state.reward_expiry + 1,
+                });
+            }
+        }
+    }
+    Ok(ExpiringResponse { claimable_reward, claimable_expire_round, rounds })
+}
+
+fn query_following_history(deps: Deps, addr: Addr) -> StdResult<Vec<FollowingHistory>> {
+    FOLLOWING_HISTORY.prefix(addr).range(deps.storage, None, None, Order::Ascending).map(|item| {
+        let (_, history) = item?;
+        Ok(history)
+    }).collect()
+}
+
+fn query_leaderboard(deps: Deps, board: LeaderboardKind, round: Option<u64>, limit: Option<u64>) -> StdResult<Vec<UserAmountInfo>> {
+    let board = match board {
+        LeaderboardKind::Volume => VOLUME_BOARD,
+        LeaderboardKind::Reward => REWARD_BOARD,
     };
+    let top = board.top(deps.storage, round.unwrap_or(ALL_TIME), limit.unwrap_or(20) as usize)?;
+    Ok(top.into_iter().map(|(address, amount)| UserAmountInfo { address, amount }).collect())
+}
+
+fn query_referrals(deps: Deps, start_after: Option<Addr>, limit: Option<u64>) -> StdResult<Vec<Referral>> {
+    let start = start_after.map(|addr| Bound::ExclusiveRaw(addr.as_bytes().to_vec()));
     let referrals: Vec<Referral> = REFERRALS.range(deps.storage, start, None, Order::Ascending).take(limit.unwrap_or(20) as usize).map(|item| {
         let (_, referral) = item.unwrap();
         referral
@@ -469,6 +1298,7 @@ fn query_reward(deps: Deps, env: Env, addr: Addr) -> StdResult<Uint128> {
         0
     };
     let current_round = get_round(env.block.height).unwrap();
+    let start_round = std::cmp::max(start_round, expire_before(&state, current_round));
     let start = Some(Bound::InclusiveRaw(start_round.to_be_bytes().to_vec()));
     let end = Some(Bound::ExclusiveRaw(current_round.to_be_bytes().to_vec()));
 
@@ -486,4 +1316,31 @@ fn query_reward(deps: Deps, env: Env, addr: Addr) -> StdResult<Uint128> {
 
 fn query_last_claim_round(deps: Deps, _env: Env, addr: Addr) -> StdResult<u64> {
     Ok(LAST_CLAIM_ROUND.load(deps.storage, addr)?)
+}
+
+fn query_estimate_claim(deps: Deps, env: Env, addr: Addr, start_round_param: Option<u64>, max_rounds: Option<u64>) -> StdResult<ClaimEstimateResponse> {
+    let state: State = STATE.load(deps.storage)?;
+    let current_round = get_round(env.block.height).unwrap_or_default();
+    let start_round = std::cmp::max(claim_start_round(deps.storage, &addr, start_round_param)?, expire_before(&state, current_round));
+    let max_rounds = std::cmp::max(max_rounds.unwrap_or(u64::MAX), 1);
+
+    let start = Some(Bound::InclusiveRaw(start_round.to_be_bytes().to_vec()));
+    let end = Some(Bound::ExclusiveRaw(current_round.to_be_bytes().to_vec()));
+    let mut rounds_remaining = 0u64;
+    let mut window_rounds = max_rounds;
+    let mut windows: Vec<ClaimWindow> = vec![];
+    for item in ROUNDS.range(deps.storage, start, end, Order::Ascending) {
+        let (key, round_info) = item?;
+        let my_share = REWARD_SHARE.may_load(deps.storage, (key, addr.clone()))?.unwrap_or_default();
+        if window_rounds == max_rounds {
+            windows.push(ClaimWindow { start_round: key, end_round: key, reward: Uint128::zero() });
+            window_rounds = 0;
+        }
+        let window = windows.last_mut().unwrap();
+        window.end_round = key;
+        window.reward += my_share * round_info.reward_ratio;
+        window_rounds += 1;
+        rounds_remaining += 1;
+    }
+    Ok(ClaimEstimateResponse { rounds_remaining, windows })
 }
\ No newline at end of file
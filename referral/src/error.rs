@@ -21,4 +21,16 @@ pub enum ContractError {
     #[error("NotEnough")]
     NotEnough {},
 
+    #[error("InvalidCode")]
+    InvalidCode {},
+
+    #[error("InvalidTier")]
+    InvalidTier {},
+
+    #[error("InvalidRatio")]
+    InvalidRatio {},
+
+    #[error("Cooldown")]
+    Cooldown {},
+
 }
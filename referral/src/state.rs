@@ -1,8 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128, Decimal};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Uint128, Decimal, Order, StdResult, Storage};
+use zerosum::referral::{Tier, FollowingHistory, Qualification};
+use zerosum::asset::{Asset};
+use cw_storage_plus::{Bound, Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
@@ -14,35 +16,90 @@ pub struct State {
     pub referral_ratio: Vec<Decimal>,
     pub collector_contract: Addr,
     pub reward_contract: Addr,
+    #[serde(default)]
+    pub rename_code_fee: Uint128,
+    // rounds between changes of following
+    #[serde(default)]
+    pub following_cooldown: u64,
+    // rounds until unclaimed rewards expire, 0 never expires
+    #[serde(default)]
+    pub reward_expiry: u64,
+    // rounds a referrer is registered before it gets a refund
+    #[serde(default)]
+    pub deregister_tenure: u64,
+    #[serde(default)]
+    pub deregister_refund_ratio: Decimal,
+    // registration fees held until the referrers deregister
+    #[serde(default)]
+    pub total_fees: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct RoundInfo {
     pub reward_ratio: Decimal,
+    // 아직 클레임되지 않은 리워드, None 이면 추적하지 않은 이전 라운드
+    #[serde(default)]
+    pub unclaimed: Option<Uint128>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Referral {
     pub name: String,
     pub description: String,
+    #[serde(default)]
+    pub code: Option<String>,
     // pub total_reward: Uint128,
+    #[serde(default)]
+    pub fee: Uint128,
+    #[serde(default)]
+    pub registered_round: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierConfig {
+    pub window: u64,
+    pub tiers: Vec<Tier>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct Reward {
     pub claimable_reward: Uint128,
     pub total_reward: Uint128,
+    // 마지막 클레임 (또는 리워드 적립) 라운드
+    #[serde(default)]
+    pub last_round: Option<u64>,
+    // 하우스 수익에서 받은 자산별 리워드
+    #[serde(default)]
+    pub claimable_assets: Vec<Asset>,
+    #[serde(default)]
+    pub total_assets: Vec<Asset>,
 }
 
 pub const STATE: Item<State> = Item::new("state");
 pub const REFERRALS: Map<Addr, Referral> = Map::new("referrals");
+// 직접 팔로워에게 돌려주는 비율
+pub const KICKBACKS: Map<Addr, Decimal> = Map::new("kickbacks");
+// lowercase code, referrer
+pub const REFERRAL_CODES: Map<&str, Addr> = Map::new("referral_codes");
 
 pub const FOLLOWING: Map<Addr, Addr> = Map::new("following");
 pub const FOLLOWERS: Map<(Addr, u64), Addr> = Map::new("followers");
 
 pub const FOLLOWER_IDX: Map<Addr, u64> = Map::new("follower_idx");
+// 팔로워의 FOLLOWERS index
+pub const FOLLOWER_KEY: Map<Addr, u64> = Map::new("follower_key");
+// referrer, follower → FOLLOWERS index, 주소 순으로 팔로워를 조회
+pub const FOLLOWER_ADDRS: Map<(Addr, Addr), u64> = Map::new("follower_addrs");
+// 마지막으로 팔로잉한 라운드
+pub const FOLLOWING_SINCE: Map<Addr, u64> = Map::new("following_since");
+// 이전 팔로잉 (follower, index)
+pub const FOLLOWING_HISTORY: Map<(Addr, u64), FollowingHistory> = Map::new("following_history");
 
 pub const LAST_CLAIM_ROUND: Map<Addr, u64> = Map::new("last_claim_round");
+// owner, delegate
+pub const CLAIM_DELEGATES: Map<Addr, Addr> = Map::new("claim_delegates");
+// 다음에 스윕할 라운드
+pub const SWEEP_ROUND: Item<u64> = Item::new("sweep_round");
 // 해당 라운드의 특정유저의 Reward 지분
 pub const REWARD_SHARE: Map<(u64, Addr), Uint128> = Map::new("reward_share");
 // 해당 라운드의 전체 Reward 전체지분
@@ -51,5 +108,80 @@ pub const TOTAL_REWARD_SHARE: Map<u64, Uint128> = Map::new("total_reward_share")
 pub const REWARDS: Map<Addr, Reward> = Map::new("rewards");
 // 레퍼럴로 누가 누구에게 얼마나 리워드를 지급했는지 팔로잉, 팔로워 순서
 pub const REFERRAL_HISTORY: Map<(Addr, Addr), Uint128> = Map::new("refferal_history");
+// 레퍼럴이 팔로워에게 돌려준 kickback 누적, 팔로워, 팔로잉 순서
+pub const KICKBACK_HISTORY: Map<(Addr, Addr), Uint128> = Map::new("kickback_history");
+
+pub const ROUNDS: Map<u64, RoundInfo> = Map::new("rounds");
+
+pub const TIERS: Item<TierConfig> = Item::new("tiers");
+pub const QUALIFICATION: Item<Qualification> = Item::new("qualification");
+// (referrer, round) 볼륨이 `min_follower_volume` 이상인 팔로워 수
+pub const ACTIVE_FOLLOWERS: Map<(Addr, u64), u64> = Map::new("active_followers");
+// 팔로워들의 라운드별 지분 합계 (referrer, round)
+pub const VOLUME_BOARD: Leaderboard = Leaderboard::new("referrer_volume", "volume_rank");
+// 라운드별 레퍼럴 리워드
+pub const REWARD_BOARD: Leaderboard = Leaderboard::new("referrer_reward", "reward_rank");
+// 레벨별 하위 팔로워 수
+pub const DOWNLINE: Map<Addr, Vec<u64>> = Map::new("downline");
+// 팔로워를 다 옮기지 못한 탈퇴 레퍼럴, 탈퇴한 라운드
+pub const DEREGISTERED: Map<Addr, u64> = Map::new("deregistered");
+
+// period of the all-time scores of a leaderboard
+pub const ALL_TIME: u64 = u64::MAX;
+
+/// Scores per address and period (a round or [`ALL_TIME`]) with a ranking of each period.
+pub struct Leaderboard<'a> {
+    // address, period
+    scores: Map<'a, (Addr, u64), Uint128>,
+    // period, score followed by the address
+    ranks: Map<'a, (u64, Vec<u8>), Addr>,
+}
+
+impl<'a> Leaderboard<'a> {
+    pub const fn new(scores_namespace: &'a str, ranks_namespace: &'a str) -> Self {
+        Leaderboard {
+            scores: Map::new(scores_namespace),
+            ranks: Map::new(ranks_namespace),
+        }
+    }
+
+    pub fn add(&self, store: &mut dyn Storage, addr: &Addr, period: u64, amount: Uint128) -> StdResult<Uint128> {
+        let prev = self.score(store, addr, period)?;
+        if !prev.is_zero() {
+            self.ranks.remove(store, (period, rank_key(prev, addr)));
+        }
+        let score = prev + amount;
+        self.scores.save(store, (addr.clone(), period), &score)?;
+        self.ranks.save(store, (period, rank_key(score, addr)), addr)?;
+        Ok(score)
+    }
+
+    pub fn score(&self, store: &dyn Storage, addr: &Addr, period: u64) -> StdResult<Uint128> {
+        Ok(self.scores.may_load(store, (addr.clone(), period))?.unwrap_or_default())
+    }
+
+    // sum of the rounds from `start` up to `end` (exclusive)
+    pub fn sum(&self, store: &dyn Storage, addr: &Addr, start: u64, end: u64) -> StdResult<Uint128> {
+        let start = Some(Bound::InclusiveRaw(start.to_be_bytes().to_vec()));
+        let end = Some(Bound::ExclusiveRaw(end.to_be_bytes().to_vec()));
+        self.scores.prefix(addr.clone()).range(store, start, end, Order::Ascending).try_fold(Uint128::zero(), |total, item| {
+            let (_, score) = item?;
+            Ok(total + score)
+        })
+    }
+
+    // highest scores of the period first
+    pub fn top(&self, store: &dyn Storage, period: u64, limit: usize) -> StdResult<Vec<(Addr, Uint128)>> {
+        self.ranks.prefix(period).range(store, None, None, Order::Descending).take(limit).map(|item| {
+            let (_, addr) = item?;
+            let score = self.score(store, &addr, period)?;
+            Ok((addr, score))
+        }).collect()
+    }
+}
 
-pub const ROUNDS: Map<u64, RoundInfo> = Map::new("rounds");
\ No newline at end of file
+fn rank_key(score: Uint128, addr: &Addr) -> Vec<u8> {
+    let mut key = score.u128().to_be_bytes().to_vec();
+    key.extend(addr.as_bytes());
+    key
+}
\ No newline at end of file
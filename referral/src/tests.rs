@@ -1,20 +1,24 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_binary, from_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Addr, Uint128, Order, Decimal, CosmosMsg, WasmMsg, Timestamp};
+    StdResult, Addr, Uint128, Order, Decimal, CosmosMsg, WasmMsg, Timestamp, SubMsg};
 use cw2::set_contract_version;
-use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg};
+use cw20::{Cw20ReceiveMsg, Cw20ExecuteMsg, Cw20QueryMsg, BalanceResponse};
 use cw_storage_plus::{Bound};
 
-use zerosum::referral::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, RoundShareInfo, UserAmountInfo};
+use zerosum::referral::{ExecuteMsg, InstantiateMsg, QueryMsg, Cw20HookMsg, RoundShareInfo, UserAmountInfo, ReferralCodeResponse,
+    Tier, TiersResponse, ReferrerTierResponse, DownlineResponse, ReferralInfo, FollowerInfo, LeaderboardKind, FollowingHistory,
+    ClaimEstimateResponse, ClaimWindow, ExpiringResponse, ExpiringRound, Qualification, QualifiedResponse};
 use zerosum::round::{get_round};
+use zerosum::reward::{reward_msg};
+use zerosum::asset::{native_asset, token_asset};
 
 use crate::error::ContractError;
 use crate::state::{State, STATE, RoundInfo, Referral, REFERRALS, REWARD_SHARE, REFERRAL_HISTORY, 
-    REWARDS, ROUNDS, LAST_CLAIM_ROUND, FOLLOWING, FOLLOWERS, FOLLOWER_IDX};
+    REWARDS, ROUNDS, LAST_CLAIM_ROUND, FOLLOWING, FOLLOWERS, FOLLOWER_IDX, FOLLOWING_SINCE, Reward};
 
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{coins};
+use cosmwasm_std::{attr, coins, BankMsg, ContractResult, SystemResult, WasmQuery};
 
 use crate::contract::{execute, query, instantiate};
 
@@ -34,6 +38,11 @@ fn mock_instantiate(deps: DepsMut) {
         referral_ratio: Some(vec![Decimal::percent(75), Decimal::percent(5), Decimal::percent(8), Decimal::percent(5), Decimal::percent(3), Decimal::percent(2), Decimal::percent(1)]),
         collector_contract: Some(Addr::unchecked(COLLECTOR)),
         reward_contract: None,
+        rename_code_fee: Some(Uint128::from(5000u64)),
+        following_cooldown: Some(2),
+        reward_expiry: Some(3),
+        deregister_tenure: Some(10),
+        deregister_refund_ratio: Some(Decimal::percent(50)),
     };
 
     let info = mock_info(CREATOR, &[]);
@@ -50,6 +59,7 @@ fn mock_register_referral(deps: DepsMut, address: &str) {
             addr: None,
             name: Some("상위".to_string()),
             description: Some("상위 레퍼럴".to_string()),
+            code: None,
         }).unwrap()
     });
     let _res = execute(deps, mock_env(), info.clone(), msg.clone()).unwrap();
@@ -109,6 +119,12 @@ fn proper_initialization() {
             referral_ratio: vec![Decimal::percent(75), Decimal::percent(5), Decimal::percent(8), Decimal::percent(5), Decimal::percent(3), Decimal::percent(2), Decimal::percent(1)],
             collector_contract: Addr::unchecked("collector"),
             reward_contract: Addr::unchecked("reward"),
+            rename_code_fee: Uint128::from(5000u64),
+            following_cooldown: 2,
+            reward_expiry: 3,
+            deregister_tenure: 10,
+            deregister_refund_ratio: Decimal::percent(50),
+            total_fees: Uint128::zero(),
         }
     );
 }
@@ -128,6 +144,11 @@ fn update_state() {
         referral_ratio: Some(vec![Decimal::percent(75), Decimal::percent(5), Decimal::percent(8)]),
         collector_contract: Some(Addr::unchecked("collector_new")),
         reward_contract: None,
+        rename_code_fee: None,
+        following_cooldown: None,
+        reward_expiry: None,
+        deregister_tenure: None,
+        deregister_refund_ratio: None,
     };
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -145,6 +166,12 @@ fn update_state() {
             referral_ratio: vec![Decimal::percent(75), Decimal::percent(5), Decimal::percent(8)],
             collector_contract: Addr::unchecked("collector_new"),
             reward_contract: Addr::unchecked("reward"),
+            rename_code_fee: Uint128::from(5000u64),
+            following_cooldown: 2,
+            reward_expiry: 3,
+            deregister_tenure: 10,
+            deregister_refund_ratio: Decimal::percent(50),
+            total_fees: Uint128::zero(),
         }
     );
 
@@ -158,6 +185,11 @@ fn update_state() {
         referral_ratio: Some(vec![Decimal::percent(60), Decimal::percent(30)]),
         collector_contract: None,
         reward_contract: None,
+        rename_code_fee: None,
+        following_cooldown: None,
+        reward_expiry: None,
+        deregister_tenure: None,
+        deregister_refund_ratio: None,
     };
     let result = execute(deps.as_mut(), mock_env(), info, msg.clone());
     match result {
@@ -182,6 +214,12 @@ fn update_state() {
             referral_ratio: vec![Decimal::percent(60), Decimal::percent(30)],
             collector_contract: Addr::unchecked("collector_new"),
             reward_contract: Addr::unchecked("reward"),
+            rename_code_fee: Uint128::from(5000u64),
+            following_cooldown: 2,
+            reward_expiry: 3,
+            deregister_tenure: 10,
+            deregister_refund_ratio: Decimal::percent(50),
+            total_fees: Uint128::zero(),
         }
     );
 }
@@ -200,6 +238,7 @@ fn register_referral() {
             addr: None,
             name: Some("상위".to_string()),
             description: Some("상위 레퍼럴".to_string()),
+            code: None,
         }).unwrap()
     });
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
@@ -217,6 +256,7 @@ fn register_referral() {
             addr: None,
             name: Some("상위".to_string()),
             description: Some("상위 레퍼럴".to_string()),
+            code: None,
         }).unwrap()
     });
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone());
@@ -234,6 +274,7 @@ fn register_referral() {
             addr: None,
             name: Some("상위".to_string()),
             description: Some("상위 레퍼럴".to_string()),
+            code: None,
         }).unwrap()
     });
     let _res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
@@ -246,6 +287,9 @@ fn register_referral() {
         Referral {
             name: "상위".to_string(),
             description: "상위 레퍼럴".to_string(),
+            code: None,
+            fee: Uint128::from(10000000u64),
+            registered_round: Some(1234),
         }
     )
 }
@@ -430,6 +474,7 @@ fn add_round() {
     let res: RoundInfo = from_binary(&res).unwrap();
     assert_eq!(res, RoundInfo {
         reward_ratio: Decimal::percent(1000),
+        unclaimed: Some(Uint128::from(3000000u64)),
     });
 }
 
@@ -474,6 +519,9 @@ fn claim() {
     let info = mock_info("USER1", &vec![]);
     let msg = ExecuteMsg::Claim {
         start_round: None,
+        max_rounds: None,
+        owner: None,
+        recipient: None,
     };
     let res = execute(deps.as_mut(), mock_env_height(30_000), info.clone(), msg).unwrap();
     
@@ -509,6 +557,9 @@ fn claim() {
 
     let msg = ExecuteMsg::Claim {
         start_round: None,
+        max_rounds: None,
+        owner: None,
+        recipient: None,
     };
     let info = mock_info("USER10", &vec![]);
     let res = execute(deps.as_mut(), mock_env_height(30_000), info.clone(), msg.clone()).unwrap();
@@ -552,7 +603,7 @@ fn claim() {
 
 
     let info = mock_info("USER1", &vec![]);
-    let msg = ExecuteMsg::ClaimReferral { };
+    let msg = ExecuteMsg::ClaimReferral { owner: None, recipient: None };
     let res = execute(deps.as_mut(), mock_env_height(30_000), info.clone(), msg).unwrap();
 
 
@@ -560,6 +611,740 @@ fn claim() {
     let res: Uint128 = from_binary(&res).unwrap();
     assert_eq!(res, Uint128::from(0u64));
 }
+#[test]
+fn referral_code() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+
+    let register = |code: &str| ExecuteMsg::Receive(Cw20ReceiveMsg {
+        amount: Uint128::from(10000000u64),
+        sender: "alice".to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterReferral {
+            addr: None,
+            name: None,
+            description: None,
+            code: Some(code.to_string()),
+        }).unwrap()
+    });
+
+    // 링크에 쓸 수 없는 코드
+    let info = mock_info(ZEROSUM_TOKEN, &vec![]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), register("al"));
+    match res {
+        Err(ContractError::InvalidCode {}) => {}
+        _ => panic!("must be error"),
+    }
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), register("al ice"));
+    match res {
+        Err(ContractError::InvalidCode {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), register("Alice")).unwrap();
+
+    // 대소문자 구분 없이 중복
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        amount: Uint128::from(10000000u64),
+        sender: "bob".to_string(),
+        msg: to_binary(&Cw20HookMsg::RegisterReferral {
+            addr: None,
+            name: None,
+            description: None,
+            code: Some("ALICE".to_string()),
+        }).unwrap()
+    });
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::AlreadyExist {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ReferralByCode { code: "ALICE".to_string() }).unwrap();
+    let res: ReferralCodeResponse = from_binary(&res).unwrap();
+    assert_eq!(Addr::unchecked("alice"), res.address);
+    assert_eq!("alice", res.code);
+
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::AddFollowingByCode { code: "aLiCe".to_string() };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowing { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Addr = from_binary(&res).unwrap();
+    assert_eq!(Addr::unchecked("alice"), res);
+
+    // 수수료가 부족할때
+    let info = mock_info(ZEROSUM_TOKEN, &vec![]);
+    let rename = |amount: u64| ExecuteMsg::Receive(Cw20ReceiveMsg {
+        amount: Uint128::from(amount),
+        sender: "alice".to_string(),
+        msg: to_binary(&Cw20HookMsg::RenameCode { code: "alice2".to_string() }).unwrap()
+    });
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), rename(4999));
+    match res {
+        Err(ContractError::NotEnough {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 수수료는 collector로
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), rename(5000)).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: ZEROSUM_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient: COLLECTOR.to_string(), amount: Uint128::from(5000u64) }).unwrap(),
+        funds: vec![],
+    }));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ReferralByCode { code: "alice".to_string() });
+    assert!(res.is_err());
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ReferralByCode { code: "alice2".to_string() }).unwrap();
+    let res: ReferralCodeResponse = from_binary(&res).unwrap();
+    assert_eq!(Addr::unchecked("alice"), res.address);
+}
+
+#[test]
+fn tiers() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_following(deps.as_mut(), "user1", "ref1");
+
+    let bronze = Tier { name: "bronze".to_string(), min_volume: Uint128::from(1000u64), level_ratios: vec![Decimal::percent(5), Decimal::percent(10)] };
+    let gold = Tier { name: "gold".to_string(), min_volume: Uint128::from(5000u64), level_ratios: vec![Decimal::percent(5), Decimal::percent(20)] };
+
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::UpdateTiers { window: 2, tiers: vec![bronze.clone(), gold.clone()] };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 순서가 잘못된 티어
+    let info = mock_info(CREATOR, &vec![]);
+    let msg = ExecuteMsg::UpdateTiers { window: 2, tiers: vec![gold.clone(), bronze.clone()] };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidTier {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 100%를 넘는 비율
+    let greedy = Tier { name: "greedy".to_string(), min_volume: Uint128::from(1u64), level_ratios: vec![Decimal::percent(5), Decimal::percent(30)] };
+    let msg = ExecuteMsg::UpdateTiers { window: 2, tiers: vec![greedy] };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidTier {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let msg = ExecuteMsg::UpdateTiers { window: 2, tiers: vec![bronze.clone(), gold.clone()] };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetTiers {}).unwrap();
+    let res: TiersResponse = from_binary(&res).unwrap();
+    assert_eq!(res, TiersResponse { window: 2, tiers: vec![bronze.clone(), gold.clone()] });
+
+    // round 1
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+
+    let res = query(deps.as_ref(), mock_env_height(24), QueryMsg::GetReferrerTier { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: ReferrerTierResponse = from_binary(&res).unwrap();
+    assert_eq!(res, ReferrerTierResponse {
+        volume: Uint128::from(3000u64),
+        tier: Some(bronze),
+        next_tier: Some(gold),
+        remaining_volume: Uint128::from(2000u64),
+    });
+
+    // bronze 티어의 10% (9999 * 10%)
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: Some(0), max_rounds: None, owner: None, recipient: None };
+    let _res = execute(deps.as_mut(), mock_env_height(24), info, msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(999u64));
+
+    // 윈도우가 지나면 티어가 사라짐
+    let res = query(deps.as_ref(), mock_env_height(44), QueryMsg::GetReferrerTier { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: ReferrerTierResponse = from_binary(&res).unwrap();
+    assert_eq!(res.volume, Uint128::zero());
+    assert_eq!(res.tier, None);
+}
+
+#[test]
+fn kickback() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_following(deps.as_mut(), "user1", "ref1");
+
+    // 레퍼럴이 아닐때
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::SetKickback { ratio: Decimal::percent(50) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::NotReferral {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let info = mock_info("ref1", &vec![]);
+    let msg = ExecuteMsg::SetKickback { ratio: Decimal::percent(101) };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    match res {
+        Err(ContractError::InvalidRatio {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let msg = ExecuteMsg::SetKickback { ratio: Decimal::percent(50) };
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetKickback { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Decimal = from_binary(&res).unwrap();
+    assert_eq!(res, Decimal::percent(50));
+
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+
+    // 9999 * 8% = 799 중 절반(399)은 user1에게
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: Some(0), max_rounds: None, owner: None, recipient: None };
+    let res = execute(deps.as_mut(), mock_env_height(24), info, msg).unwrap();
+    assert_eq!(res.messages.last().unwrap().msg, reward_msg(Addr::unchecked(""), Addr::unchecked("user1"), Uint128::from(7499u64 + 399u64)).unwrap());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(400u64));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowers { reward_addr: Addr::unchecked("ref1"), target_addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Vec<UserAmountInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![UserAmountInfo { address: Addr::unchecked("user1"), amount: Uint128::from(400u64) }]);
+
+    // kickback은 레퍼럴 리워드 기록과 따로 남음
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetKickbackHistory { follower: Addr::unchecked("user1"), referrer: Addr::unchecked("ref1") }).unwrap();
+    let res: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(res, Uint128::from(399u64));
+    assert_eq!(REFERRAL_HISTORY.may_load(deps.as_ref().storage, (Addr::unchecked("user1"), Addr::unchecked("ref1"))).unwrap(), None);
+}
+
+#[test]
+fn referral_tree() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+    mock_register_referral(deps.as_mut(), "ref3");
+    mock_following(deps.as_mut(), "ref2", "ref1");
+    mock_following(deps.as_mut(), "user1", "ref2");
+    mock_following(deps.as_mut(), "user2", "ref2");
+    // 이미 팔로워가 있는 레퍼럴이 팔로잉
+    mock_following(deps.as_mut(), "user3", "ref3");
+    mock_following(deps.as_mut(), "ref3", "ref1");
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDownline { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: DownlineResponse = from_binary(&res).unwrap();
+    assert_eq!(res, DownlineResponse { levels: vec![2, 3, 0, 0, 0, 0, 0], total: 5 });
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetUpline { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Vec<Addr> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![Addr::unchecked("ref2"), Addr::unchecked("ref1")]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListReferrals { start_after: Some(Addr::unchecked("ref1")), limit: Some(1) }).unwrap();
+    let res: Vec<ReferralInfo> = from_binary(&res).unwrap();
+    assert_eq!(res.iter().map(|referral| referral.address.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("ref2")]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListFollowers { addr: Addr::unchecked("ref2"), start_after: Some(Addr::unchecked("user1")), limit: None }).unwrap();
+    let res: Vec<FollowerInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![FollowerInfo { index: 1, address: Addr::unchecked("user2"), amount: Uint128::zero() }]);
+
+    mock_add_share(deps.as_mut(), 14, "user1", 1000);
+    mock_add_share(deps.as_mut(), 14, "user3", 3000);
+    mock_add_share(deps.as_mut(), 24, "user2", 5000);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLeaderboard { board: LeaderboardKind::Volume, round: Some(1), limit: None }).unwrap();
+    let res: Vec<UserAmountInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![
+        UserAmountInfo { address: Addr::unchecked("ref3"), amount: Uint128::from(3000u64) },
+        UserAmountInfo { address: Addr::unchecked("ref2"), amount: Uint128::from(1000u64) },
+    ]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLeaderboard { board: LeaderboardKind::Volume, round: None, limit: None }).unwrap();
+    let res: Vec<UserAmountInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![
+        UserAmountInfo { address: Addr::unchecked("ref2"), amount: Uint128::from(6000u64) },
+        UserAmountInfo { address: Addr::unchecked("ref3"), amount: Uint128::from(3000u64) },
+    ]);
+
+    // 7500 중 ref3 8%, ref1 5%
+    mock_add_round(deps.as_mut(), 1, 10000);
+    let info = mock_info("user3", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: Some(0), max_rounds: None, owner: None, recipient: None };
+    let _res = execute(deps.as_mut(), mock_env_height(24), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLeaderboard { board: LeaderboardKind::Reward, round: Some(2), limit: Some(1) }).unwrap();
+    let res: Vec<UserAmountInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![UserAmountInfo { address: Addr::unchecked("ref3"), amount: Uint128::from(600u64) }]);
+}
+
+#[test]
+fn change_following() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::ChangeFollowing { address: Addr::unchecked("ref2") };
+    let res = execute(deps.as_mut(), mock_env_height(4), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::NotExist {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 0 라운드에 팔로잉
+    let _res = execute(deps.as_mut(), mock_env_height(4), info.clone(), ExecuteMsg::AddFollowing { address: Addr::unchecked("ref1") }).unwrap();
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+
+    // 쿨다운 2 라운드
+    let res = execute(deps.as_mut(), mock_env_height(14), info.clone(), msg.clone());
+    match res {
+        Err(ContractError::Cooldown {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let _res = execute(deps.as_mut(), mock_env_height(24), info.clone(), msg).unwrap();
+    mock_add_share(deps.as_mut(), 24, "user1", 3000);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowingHistory { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Vec<FollowingHistory> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![FollowingHistory { referrer: Addr::unchecked("ref1"), to_round: 2 }]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListFollowers { addr: Addr::unchecked("ref1"), start_after: None, limit: None }).unwrap();
+    let res: Vec<FollowerInfo> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListFollowers { addr: Addr::unchecked("ref2"), start_after: None, limit: None }).unwrap();
+    let res: Vec<FollowerInfo> = from_binary(&res).unwrap();
+    assert_eq!(res.iter().map(|follower| follower.address.clone()).collect::<Vec<Addr>>(), vec![Addr::unchecked("user1")]);
+
+    // 1 라운드 리워드는 ref1, 2 라운드 리워드는 ref2
+    mock_add_round(deps.as_mut(), 1, 10000);
+    mock_add_round(deps.as_mut(), 2, 10000);
+    let msg = ExecuteMsg::Claim { start_round: Some(0), max_rounds: None, owner: None, recipient: None };
+    let _res = execute(deps.as_mut(), mock_env_height(34), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(799u64));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref2") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(799u64));
+
+    // 순환 팔로잉
+    mock_register_referral(deps.as_mut(), "ref3");
+    mock_following(deps.as_mut(), "ref3", "ref2");
+    mock_following(deps.as_mut(), "ref1", "ref3");
+    let info = mock_info("ref3", &vec![]);
+    let msg = ExecuteMsg::ChangeFollowing { address: Addr::unchecked("ref1") };
+    let res = execute(deps.as_mut(), mock_env_height(12365), info, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+}
+
+#[test]
+fn claim_max_rounds() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_following(deps.as_mut(), "user1", "ref1");
+
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_share(deps.as_mut(), 24, "user1", 3000);
+    mock_add_share(deps.as_mut(), 34, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+    mock_add_round(deps.as_mut(), 2, 10000);
+    mock_add_round(deps.as_mut(), 3, 10000);
+
+    let res = query(deps.as_ref(), mock_env_height(44), QueryMsg::EstimateClaim { addr: Addr::unchecked("user1"), start_round: Some(0), max_rounds: Some(2) }).unwrap();
+    let res: ClaimEstimateResponse = from_binary(&res).unwrap();
+    assert_eq!(res, ClaimEstimateResponse {
+        rounds_remaining: 3,
+        windows: vec![
+            ClaimWindow { start_round: 1, end_round: 2, reward: Uint128::from(19998u64) },
+            ClaimWindow { start_round: 3, end_round: 3, reward: Uint128::from(9999u64) },
+        ],
+    });
+
+    // 2 라운드까지만 클레임
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: Some(0), max_rounds: Some(2), owner: None, recipient: None };
+    let res = execute(deps.as_mut(), mock_env_height(44), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages.last().unwrap().msg, reward_msg(Addr::unchecked(""), Addr::unchecked("user1"), Uint128::from(7499u64 * 2)).unwrap());
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetLastClaimRound { addr: Addr::unchecked("user1") }).unwrap();
+    let res: u64 = from_binary(&res).unwrap();
+    assert_eq!(res, 2);
+
+    let res = execute(deps.as_mut(), mock_env_height(44), info, msg).unwrap();
+    assert_eq!(res.messages.last().unwrap().msg, reward_msg(Addr::unchecked(""), Addr::unchecked("user1"), Uint128::from(7499u64)).unwrap());
+    let res = query(deps.as_ref(), mock_env_height(44), QueryMsg::EstimateClaim { addr: Addr::unchecked("user1"), start_round: None, max_rounds: Some(2) }).unwrap();
+    let res: ClaimEstimateResponse = from_binary(&res).unwrap();
+    assert_eq!(res, ClaimEstimateResponse { rounds_remaining: 0, windows: vec![] });
+}
+
+#[test]
+fn reward_expiry() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_following(deps.as_mut(), "user1", "ref1");
+    mock_following(deps.as_mut(), "user2", "ref1");
+
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_share(deps.as_mut(), 14, "user2", 1000);
+    mock_add_share(deps.as_mut(), 24, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+    mock_add_round(deps.as_mut(), 2, 10000);
+
+    // 1 라운드는 5 라운드부터 만료
+    let res = query(deps.as_ref(), mock_env_height(44), QueryMsg::GetExpiring { addr: Addr::unchecked("user1"), start_round: None }).unwrap();
+    let res: ExpiringResponse = from_binary(&res).unwrap();
+    assert_eq!(res, ExpiringResponse {
+        claimable_reward: Uint128::zero(),
+        claimable_expire_round: None,
+        rounds: vec![
+            ExpiringRound { round: 1, reward: Uint128::from(7500u64), expire_round: 5 },
+            ExpiringRound { round: 2, reward: Uint128::from(9999u64), expire_round: 6 },
+        ],
+    });
+
+    // user2 는 3 라운드에 클레임
+    let info = mock_info("user2", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: None, recipient: None };
+    let _res = execute(deps.as_mut(), mock_env_height(34), info, msg).unwrap();
+    let res = query(deps.as_ref(), mock_env_height(34), QueryMsg::GetExpiring { addr: Addr::unchecked("ref1"), start_round: None }).unwrap();
+    let res: ExpiringResponse = from_binary(&res).unwrap();
+    assert_eq!((res.claimable_reward, res.claimable_expire_round), (Uint128::from(200u64), Some(7)));
+
+    // 5 라운드에 user1 은 2 라운드만 클레임
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: None, recipient: None };
+    let res = execute(deps.as_mut(), mock_env_height(54), info, msg).unwrap();
+    assert_eq!(res.messages.last().unwrap().msg, reward_msg(Addr::unchecked(""), Addr::unchecked("user1"), Uint128::from(7499u64)).unwrap());
+
+    // 1 라운드의 남은 리워드 7500
+    let info = mock_info("anyone", &vec![]);
+    let msg = ExecuteMsg::SweepExpired { referrers: Some(vec![Addr::unchecked("ref1")]), limit: None };
+    let res = execute(deps.as_mut(), mock_env_height(54), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(reward_msg(Addr::unchecked(""), Addr::unchecked(COLLECTOR), Uint128::from(7500u64)).unwrap())]);
+
+    // 2 라운드의 남은 리워드, ref1 은 5 라운드 적립으로 9 라운드부터 만료
+    let res = query(deps.as_ref(), mock_env_height(54), QueryMsg::GetExpiring { addr: Addr::unchecked("ref1"), start_round: None }).unwrap();
+    let res: ExpiringResponse = from_binary(&res).unwrap();
+    assert_eq!((res.claimable_reward, res.claimable_expire_round), (Uint128::from(999u64), Some(9)));
+    let res = execute(deps.as_mut(), mock_env_height(74), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(reward_msg(Addr::unchecked(""), Addr::unchecked(COLLECTOR), Uint128::from(1u64)).unwrap())]);
+
+    // 만료된 잔액은 클레임해도 컬렉터로
+    let res = execute(deps.as_mut(), mock_env_height(94), mock_info("ref1", &vec![]), ExecuteMsg::ClaimReferral { owner: None, recipient: None }).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(reward_msg(Addr::unchecked(""), Addr::unchecked(COLLECTOR), Uint128::from(999u64)).unwrap())]);
+    let res = execute(deps.as_mut(), mock_env_height(94), info, msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+}
+
+#[test]
+fn house_edge() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+    mock_following(deps.as_mut(), "ref2", "ref1");
+    mock_following(deps.as_mut(), "user1", "ref2");
+
+    let msg = ExecuteMsg::AddEdge { address: Addr::unchecked("user1") };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &coins(1900, "uusd")), msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 8 : 5 비율로 나누고 나머지는 collector
+    let res = execute(deps.as_mut(), mock_env(), mock_info(HOUSE, &coins(1900, "uusd")), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: COLLECTOR.to_string(),
+        amount: coins(600, "uusd"),
+    }))]);
+
+    let info = mock_info("token", &vec![]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        amount: Uint128::from(190u64),
+        sender: HOUSE.to_string(),
+        msg: to_binary(&Cw20HookMsg::AddEdge { address: Addr::unchecked("user1") }).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_assets, vec![
+        native_asset("uusd".to_string(), Uint128::from(500u64)),
+        token_asset(Addr::unchecked("token"), Uint128::from(50u64)),
+    ]);
+
+    let info = mock_info("ref2", &vec![]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClaimReferral { owner: None, recipient: None }).unwrap();
+    assert_eq!(res.messages.iter().map(|msg| msg.msg.clone()).collect::<Vec<CosmosMsg>>(), vec![
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "ref2".to_string(),
+            amount: coins(800, "uusd"),
+        }),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "ref2".to_string(),
+                amount: Uint128::from(80u64),
+            }).unwrap(),
+            funds: vec![],
+        }),
+    ]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref2") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_assets, vec![]);
+    assert_eq!(res.total_assets.len(), 2);
+}
+
+fn mock_balances(balances: Vec<(&'static str, u64)>) -> impl Fn(&WasmQuery) -> cosmwasm_std::QuerierResult {
+    move |query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let address = match from_binary(msg).unwrap() {
+                Cw20QueryMsg::Balance { address } => address,
+                _ => panic!("not a balance query"),
+            };
+            let balance = balances.iter().find(|(addr, _)| *addr == address).map(|(_, balance)| *balance).unwrap_or_default();
+            SystemResult::Ok(ContractResult::Ok(to_binary(&BalanceResponse { balance: Uint128::from(balance) }).unwrap()))
+        },
+        _ => panic!("not a smart query"),
+    }
+}
+
+#[test]
+fn qualification() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+    mock_following(deps.as_mut(), "ref2", "ref1");
+    mock_following(deps.as_mut(), "user1", "ref2");
+    deps.querier.update_wasm(mock_balances(vec![("ref1", 500), ("ref2", 50)]));
+
+    let qualification = Qualification {
+        min_balance: Uint128::from(100u64),
+        min_active_followers: 2,
+        min_follower_volume: Uint128::from(1000u64),
+        max_follower_reward: Uint128::from(300u64),
+    };
+    let msg = ExecuteMsg::UpdateQualification { qualification: qualification.clone() };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &vec![]), msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+    let _res = execute(deps.as_mut(), mock_env(), mock_info(CREATOR, &vec![]), msg).unwrap();
+
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+    let res = query(deps.as_ref(), mock_env_height(24), QueryMsg::GetQualified { addr: Addr::unchecked("ref2") }).unwrap();
+    let res: QualifiedResponse = from_binary(&res).unwrap();
+    assert_eq!(res, QualifiedResponse { balance: Uint128::from(50u64), active_followers: 1, direct: false, upline: false });
+
+    // ref2 는 잔액 부족 799, ref1 은 활성 팔로워 부족 499
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: None, recipient: None };
+    let res = execute(deps.as_mut(), mock_env_height(24), info.clone(), msg.clone()).unwrap();
+    assert_eq!(res.messages[0].msg, reward_msg(Addr::unchecked(""), Addr::unchecked(COLLECTOR), Uint128::from(799u64 + 499u64)).unwrap());
+
+    // 하우스 엣지도 같은 자격, 전부 collector
+    let edge = ExecuteMsg::AddEdge { address: Addr::unchecked("user1") };
+    let res = execute(deps.as_mut(), mock_env_height(24), mock_info(HOUSE, &coins(1900, "uusd")), edge).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: COLLECTOR.to_string(),
+        amount: coins(1900, "uusd"),
+    }))]);
+
+    // ref2 는 팔로워 당 300 까지
+    deps.querier.update_wasm(mock_balances(vec![("ref1", 500), ("ref2", 500)]));
+    mock_add_share(deps.as_mut(), 24, "user1", 3000);
+    mock_add_round(deps.as_mut(), 2, 10000);
+    let res = execute(deps.as_mut(), mock_env_height(34), info, msg).unwrap();
+    assert_eq!(res.messages[0].msg, reward_msg(Addr::unchecked(""), Addr::unchecked(COLLECTOR), Uint128::from(499u64 + 499u64)).unwrap());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref2") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(300u64));
+
+    // 한도는 라운드마다, 799 는 300 으로 79 는 그대로
+    mock_add_share(deps.as_mut(), 34, "user1", 3000);
+    mock_add_round(deps.as_mut(), 3, 10000);
+    mock_add_share(deps.as_mut(), 44, "user1", 3000);
+    mock_add_round(deps.as_mut(), 4, 1000);
+    let info = mock_info("user1", &vec![]);
+    let msg = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: None, recipient: None };
+    let _res = execute(deps.as_mut(), mock_env_height(54), info, msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetReferralReward { addr: Addr::unchecked("ref2") }).unwrap();
+    let res: Reward = from_binary(&res).unwrap();
+    assert_eq!(res.claimable_reward, Uint128::from(300u64 + 300u64 + 79u64));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetQualification {}).unwrap();
+    let res: Qualification = from_binary(&res).unwrap();
+    assert_eq!(res, qualification);
+}
+
+#[test]
+fn deregister() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+    mock_following(deps.as_mut(), "ref2", "ref1");
+    mock_following(deps.as_mut(), "user1", "ref2");
+    mock_following(deps.as_mut(), "user2", "ref2");
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &vec![]), ExecuteMsg::Deregister {});
+    match res {
+        Err(ContractError::NotReferral {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    // 등록 기간이 짧아서 전부 소각
+    let res = execute(deps.as_mut(), mock_env(), mock_info("ref2", &vec![]), ExecuteMsg::Deregister {}).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: ZEROSUM_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn { amount: Uint128::from(10000000u64) }).unwrap(),
+        funds: vec![],
+    }))]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowing { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Addr = from_binary(&res).unwrap();
+    assert_eq!(res, Addr::unchecked("ref1"));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListFollowers { addr: Addr::unchecked("ref1"), start_after: None, limit: None }).unwrap();
+    let res: Vec<FollowerInfo> = from_binary(&res).unwrap();
+    assert_eq!(res.iter().map(|follower| follower.address.clone()).collect::<Vec<Addr>>(), vec![
+        Addr::unchecked("ref2"), Addr::unchecked("user1"), Addr::unchecked("user2"),
+    ]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDownline { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: DownlineResponse = from_binary(&res).unwrap();
+    assert_eq!(res, DownlineResponse { levels: vec![3, 0, 0, 0, 0, 0, 0], total: 3 });
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+    let res: State = from_binary(&res).unwrap();
+    assert_eq!(res.total_fees, Uint128::from(10000000u64));
+
+    // 10 라운드 이후 절반 환불
+    let res = execute(deps.as_mut(), mock_env_height(12445), mock_info("ref1", &vec![]), ExecuteMsg::Deregister {}).unwrap();
+    assert_eq!(res.messages.iter().map(|msg| msg.msg.clone()).collect::<Vec<CosmosMsg>>(), vec![
+        token_asset(Addr::unchecked(ZEROSUM_TOKEN), Uint128::from(5000000u64)).into_msg(&deps.as_ref().querier, Addr::unchecked("ref1")).unwrap(),
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ZEROSUM_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount: Uint128::from(5000000u64) }).unwrap(),
+            funds: vec![],
+        }),
+    ]);
+    assert!(query(deps.as_ref(), mock_env(), QueryMsg::GetFollowing { addr: Addr::unchecked("user1") }).is_err());
+    assert!(query(deps.as_ref(), mock_env(), QueryMsg::GetReferral { addr: Addr::unchecked("ref1") }).is_err());
+
+    // 탈퇴 전 라운드의 리워드는 탈퇴한 레퍼럴에게
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowingHistory { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Vec<FollowingHistory> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![
+        FollowingHistory { referrer: Addr::unchecked("ref2"), to_round: 1234 },
+        FollowingHistory { referrer: Addr::unchecked("ref1"), to_round: 1244 },
+    ]);
+}
+
+#[test]
+fn deregister_migration() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_register_referral(deps.as_mut(), "ref2");
+    mock_following(deps.as_mut(), "ref2", "ref1");
+    for i in 0..25 {
+        mock_following(deps.as_mut(), &format!("user{:02}", i), "ref2");
+    }
+
+    // 처음 20 명만 옮김
+    let res = execute(deps.as_mut(), mock_env_height(12445), mock_info("ref2", &vec![]), ExecuteMsg::Deregister {}).unwrap();
+    assert!(res.attributes.contains(&attr("remaining", "true")));
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDownline { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: DownlineResponse = from_binary(&res).unwrap();
+    assert_eq!(res.levels[0..2], [21, 5]);
+
+    let msg = ExecuteMsg::MigrateFollowers { referrer: Addr::unchecked("ref2"), limit: Some(3) };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &vec![]), msg).unwrap();
+    assert!(res.attributes.contains(&attr("migrated", "3")));
+    assert!(res.attributes.contains(&attr("remaining", "true")));
+    let msg = ExecuteMsg::MigrateFollowers { referrer: Addr::unchecked("ref2"), limit: None };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &vec![]), msg.clone()).unwrap();
+    assert!(res.attributes.contains(&attr("migrated", "2")));
+    assert!(res.attributes.contains(&attr("remaining", "false")));
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &vec![]), msg);
+    match res {
+        Err(ContractError::NotExist {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetDownline { addr: Addr::unchecked("ref1") }).unwrap();
+    let res: DownlineResponse = from_binary(&res).unwrap();
+    assert_eq!(res, DownlineResponse { levels: vec![26, 0, 0, 0, 0, 0, 0], total: 26 });
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowing { addr: Addr::unchecked("user24") }).unwrap();
+    let res: Addr = from_binary(&res).unwrap();
+    assert_eq!(res, Addr::unchecked("ref1"));
+    // 팔로잉한 라운드는 그대로
+    assert_eq!(FOLLOWING_SINCE.load(&deps.storage, Addr::unchecked("user24")).unwrap(), 1234);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetFollowingHistory { addr: Addr::unchecked("user24") }).unwrap();
+    let res: Vec<FollowingHistory> = from_binary(&res).unwrap();
+    assert_eq!(res, vec![FollowingHistory { referrer: Addr::unchecked("ref2"), to_round: 1244 }]);
+}
+
+#[test]
+fn claim_delegate() {
+    let mut deps = mock_dependencies();
+    mock_instantiate(deps.as_mut());
+    mock_register_referral(deps.as_mut(), "ref1");
+    mock_following(deps.as_mut(), "user1", "ref1");
+    mock_add_share(deps.as_mut(), 14, "user1", 3000);
+    mock_add_round(deps.as_mut(), 1, 10000);
+
+    let msg = ExecuteMsg::SetClaimDelegate { delegate: Some("partner".to_string()) };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &vec![]), msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetClaimDelegate { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Option<Addr> = from_binary(&res).unwrap();
+    assert_eq!(res, Some(Addr::unchecked("partner")));
+
+    // 위임받지 않은 주소, 다른 주소로 받는 위임
+    let msg = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: Some("user1".to_string()), recipient: None };
+    let res = execute(deps.as_mut(), mock_env_height(24), mock_info("anyone", &vec![]), msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+    let msg_to_partner = ExecuteMsg::Claim { start_round: None, max_rounds: None, owner: Some("user1".to_string()), recipient: Some("partner".to_string()) };
+    let res = execute(deps.as_mut(), mock_env_height(24), mock_info("partner", &vec![]), msg_to_partner);
+    match res {
+        Err(ContractError::Unauthorized {}) => {}
+        _ => panic!("must be error"),
+    }
+
+    let res = execute(deps.as_mut(), mock_env_height(24), mock_info("partner", &vec![]), msg).unwrap();
+    assert_eq!(res.messages.last().unwrap().msg, reward_msg(Addr::unchecked(""), Addr::unchecked("user1"), Uint128::from(7499u64)).unwrap());
+
+    // 본인은 다른 주소로 받을 수 있음
+    let msg = ExecuteMsg::ClaimReferral { owner: None, recipient: Some("wallet".to_string()) };
+    let res = execute(deps.as_mut(), mock_env_height(24), mock_info("ref1", &vec![]), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(reward_msg(Addr::unchecked(""), Addr::unchecked("wallet"), Uint128::from(799u64)).unwrap())]);
+
+    let msg = ExecuteMsg::SetClaimDelegate { delegate: None };
+    let _res = execute(deps.as_mut(), mock_env(), mock_info("user1", &vec![]), msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::GetClaimDelegate { addr: Addr::unchecked("user1") }).unwrap();
+    let res: Option<Addr> = from_binary(&res).unwrap();
+    assert_eq!(res, None);
+}
+
 // #[test]
 // fn claim_referral() {
 